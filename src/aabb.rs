@@ -0,0 +1,63 @@
+use crate::vector_3::{Vector3, Point3};
+use crate::ray::Ray;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Aabb {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Aabb {
+    pub const INFINITE: Aabb = Aabb {
+        min: V3!(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        max: V3!(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+    };
+
+    pub fn new(a: Point3, b: Point3) -> Aabb {
+        Aabb { min: Vector3::min(&a, &b), max: Vector3::max(&a, &b) }
+    }
+
+    pub fn union(a: &Aabb, b: &Aabb) -> Aabb {
+        Aabb { min: Vector3::min(&a.min, &b.min), max: Vector3::max(&a.max, &b.max) }
+    }
+
+    //NO INFINITE OR NAN CORNERS
+    pub fn is_finite(&self) -> bool {
+        (0..3).all(|axis: usize| self.min[axis].is_finite() && self.max[axis].is_finite())
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d: Vector3 = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let d: Vector3 = self.max - self.min;
+        if d.x > d.y && d.x > d.z { 0 } else if d.y > d.z { 1 } else { 2 }
+    }
+
+    //SLAB TEST
+    pub fn hit(&self, ray: &Ray, min: f32, max: f32) -> bool {
+        let mut min: f32 = min;
+        let mut max: f32 = max;
+
+        for axis in 0..3 {
+            let inverse: f32 = 1.0 / ray.direction[axis];
+            let mut t0: f32 = (self.min[axis] - ray.origin[axis]) * inverse;
+            let mut t1: f32 = (self.max[axis] - ray.origin[axis]) * inverse;
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            min = if t0 > min {t0} else {min};
+            max = if t1 < max {t1} else {max};
+            if max < min {
+                return false;
+            }
+        }
+        true
+    }
+}
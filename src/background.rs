@@ -0,0 +1,27 @@
+use crate::colour::Colour;
+use crate::vector_3::Vector3;
+
+pub mod gradient;
+pub mod solid;
+pub mod environment;
+pub mod physical_sky;
+
+//RADIANCE ARRIVING FROM INFINITELY FAR AWAY, SEEN BY RAYS THAT ESCAPE THE SCENE
+pub trait Background: Send + Sync {
+    fn colour(&self, direction: &Vector3) -> Colour;
+
+    //RANDOM DIRECTION TOWARDS THE BACKGROUND, FOR BACKGROUNDS THAT ARE SAMPLED AS LIGHTS
+    fn sample(&self, _rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        None
+    }
+
+    //SOLID ANGLE DENSITY OF sample PRODUCING direction
+    fn pdf_value(&self, _direction: &Vector3) -> f32 {
+        0.0
+    }
+
+    //LUMINANCE INTEGRATED OVER EVERY DIRECTION, FOR WEIGHING IT AGAINST THE OTHER LIGHTS
+    fn power(&self) -> f32 {
+        0.0
+    }
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::f32::consts::PI;
+
+use xorshift::Rng;
+
+use crate::background::Background;
+use crate::colour::{self, Colour};
+use crate::distribution::Distribution;
+use crate::render::Framebuffer;
+use crate::vector_3::Vector3;
+
+//EQUIRECTANGULAR MAP, U GOES AROUND +Y WITH THE CENTRE OF THE IMAGE TOWARDS -Z, V GOES DOWN FROM +Y
+pub struct Environment {
+    image: Framebuffer,
+    //RADIANS ABOUT +Y
+    rotation: f32,
+    intensity: f32,
+    //ROWS FROM THE TOP, AND THE COLUMNS OF EACH ONE, PROPORTIONAL TO LUMINANCE TIMES THE SOLID ANGLE OF A PIXEL
+    rows: Distribution,
+    columns: Vec<Distribution>,
+}
+
+//UNROTATED DIRECTION AT A POINT OF THE MAP, V FROM THE TOP
+pub fn direction(u: f32, v: f32) -> Vector3 {
+    let phi: f32 = 2.0 * PI * (u - 0.5);
+    let theta: f32 = PI * v;
+    V3!(theta.sin() * phi.sin(), theta.cos(), -theta.sin() * phi.cos())
+}
+
+fn rotate(direction: &Vector3, angle: f32) -> Vector3 {
+    let (sin, cos): (f32, f32) = angle.sin_cos();
+    V3!(cos * direction.x + sin * direction.z, direction.y, cos * direction.z - sin * direction.x)
+}
+
+impl Environment {
+    pub fn new(image: Framebuffer, rotation: f32, intensity: f32) -> Environment {
+        let (width, height): (usize, usize) = (image.width as usize, image.height as usize);
+        let columns: Vec<Distribution> = (0..height).map(|row: usize| {
+            let sin_theta: f32 = f32::sin(PI * (row as f32 + 0.5) / height as f32);
+            let weights: Vec<f32> = (0..width).map(|column: usize| colour::luminance(&image.get(column as u16, (height - 1 - row) as u16)) * sin_theta).collect();
+            Distribution::new(&weights)
+        }).collect();
+        let rows: Distribution = Distribution::new(&columns.iter().map(|distribution: &Distribution| distribution.total).collect::<Vec<f32>>());
+        Environment { image, rotation, intensity, rows, columns }
+    }
+
+    //ROW FROM THE TOP AND COLUMN OF THE PIXEL SEEN ALONG direction, AND THE SINE OF ITS POLAR ANGLE
+    fn pixel(&self, direction: &Vector3) -> (usize, usize, f32) {
+        let local: Vector3 = rotate(&direction.unit_vector(), -self.rotation);
+        let u: f32 = 0.5 + f32::atan2(local.x, -local.z) / (2.0 * PI);
+        let v: f32 = f32::acos(local.y.clamp(-1.0, 1.0)) / PI;
+        let (width, height): (usize, usize) = (self.image.width as usize, self.image.height as usize);
+        let column: usize = usize::min((u * width as f32) as usize, width - 1);
+        let row: usize = usize::min((v * height as f32) as usize, height - 1);
+        (row, column, f32::sqrt(f32::max(0.0, 1.0 - local.y * local.y)))
+    }
+}
+
+impl Background for Environment {
+    fn colour(&self, direction: &Vector3) -> Colour {
+        let (row, column, _): (usize, usize, f32) = self.pixel(direction);
+        self.intensity * self.image.get(column as u16, (self.image.height as usize - 1 - row) as u16)
+    }
+
+    fn sample(&self, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        let (row, v): (usize, f32) = self.rows.sample(rng.next_f32());
+        let (column, u): (usize, f32) = self.columns[row].sample(rng.next_f32());
+        let local: Vector3 = direction((column as f32 + u) / self.image.width as f32, (row as f32 + v) / self.image.height as f32);
+        Some(rotate(&local, self.rotation))
+    }
+
+    //DENSITY IN THE UNIT SQUARE OVER THE 2 PI^2 SIN THETA OF THE MAPPING TO SOLID ANGLE
+    fn pdf_value(&self, direction: &Vector3) -> f32 {
+        let (row, column, sin_theta): (usize, usize, f32) = self.pixel(direction);
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+        let density: f32 = self.rows.probability(row) * self.columns[row].probability(column) * self.image.width as f32 * self.image.height as f32;
+        density / (2.0 * PI * PI * sin_theta)
+    }
+
+    fn power(&self) -> f32 {
+        let pixel: f32 = (2.0 * PI / self.image.width as f32) * (PI / self.image.height as f32);
+        self.intensity * self.rows.total * pixel
+    }
+}
\ No newline at end of file
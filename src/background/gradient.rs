@@ -0,0 +1,14 @@
+use crate::background::Background;
+use crate::colour::Colour;
+use crate::vector_3::Vector3;
+
+//WHITE AT THE HORIZON BLENDING INTO LIGHT BLUE ABOVE
+pub struct Gradient;
+
+impl Background for Gradient {
+    fn colour(&self, direction: &Vector3) -> Colour {
+        let unit_direction: Vector3 = direction.unit_vector();
+        let distance: f32 = 0.5 * (unit_direction.y + 1.0);
+        (1.0 - distance) * Vector3::ONE + distance * V3!(0.5, 0.7, 1.0)
+    }
+}
\ No newline at end of file
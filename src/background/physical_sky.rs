@@ -0,0 +1,172 @@
+use std::f32::consts::PI;
+
+use xorshift::Rng;
+
+use crate::background::Background;
+use crate::background::environment::{self, Environment};
+use crate::colour::{self, Colour};
+use crate::render::Framebuffer;
+use crate::vector_3::Vector3;
+
+//RESOLUTION OF THE TABLE THE SKY IS IMPORTANCE SAMPLED FROM
+const TABLE_WIDTH: u16 = 128;
+const TABLE_HEIGHT: u16 = 64;
+
+//KCD/M^2 TO RENDER UNITS, A WHITE GROUND UNDER A HIGH SUN COMES OUT AROUND 1
+const SCALE: f32 = 0.025;
+
+//LUMINANCE OF THE SUN OUTSIDE THE ATMOSPHERE IN KCD/M^2, OVER ITS REAL ANGULAR RADIUS
+const SUN_LUMINANCE: f32 = 2.0e6;
+const SUN_RADIUS: f32 = 0.00465;
+
+//PREETHAM, SHIRLEY AND SMITS 1999, LUMINANCE AND CHROMATICITY OF THE CLEAR SKY
+struct Perez {
+    sun: Vector3,
+    //ZENITH VALUES OVER THE DISTRIBUTION AT THE ZENITH, FOR Y, x AND y
+    zenith: [f32; 3],
+    coefficients: [[f32; 5]; 3],
+    scale: f32,
+}
+
+//(1 + A e^(B / cos theta)) (1 + C e^(D gamma) + E cos^2 gamma)
+fn distribution(coefficients: &[f32; 5], cos_theta: f32, gamma: f32) -> f32 {
+    let [a, b, c, d, e]: [f32; 5] = *coefficients;
+    (1.0 + a * f32::exp(b / cos_theta)) * (1.0 + c * f32::exp(d * gamma) + e * gamma.cos() * gamma.cos())
+}
+
+fn rgb(luminance: f32, x: f32, y: f32) -> Colour {
+    let (cx, cy, cz): (f32, f32, f32) = (x / y * luminance, luminance, (1.0 - x - y) / y * luminance);
+    let colour: Colour = V3!(
+        3.2406 * cx - 1.5372 * cy - 0.4986 * cz,
+        -0.9689 * cx + 1.8758 * cy + 0.0415 * cz,
+        0.0557 * cx - 0.2040 * cy + 1.0570 * cz
+    );
+    Vector3::max(&colour, &Vector3::ZERO)
+}
+
+//1 - COS, WITHOUT THE CANCELLATION THAT RUINS IT FOR SMALL ANGLES
+fn versine(angle: f32) -> f32 {
+    2.0 * f32::sin(0.5 * angle) * f32::sin(0.5 * angle)
+}
+
+//RAYLEIGH AND AEROSOL EXTINCTION ALONG THE PATH OF THE SUNLIGHT, AT RED, GREEN AND BLUE WAVELENGTHS IN MICROMETRES
+fn transmittance(theta: f32, turbidity: f32) -> Colour {
+    let air_mass: f32 = 1.0 / (theta.cos() + 0.15 * f32::powf(93.885 - theta.to_degrees(), -1.253));
+    let beta: f32 = 0.04608 * turbidity - 0.04586;
+    let channel = |wavelength: f32| -> f32 {
+        let rayleigh: f32 = 0.008735 * wavelength.powf(-4.08);
+        let aerosol: f32 = beta * wavelength.powf(-1.3);
+        f32::exp(-(rayleigh + aerosol) * air_mass)
+    };
+    V3!(channel(0.68), channel(0.55), channel(0.44))
+}
+
+impl Perez {
+    fn new(sun: Vector3, turbidity: f32, scale: f32) -> Perez {
+        let theta: f32 = f32::acos(sun.y.clamp(-1.0, 1.0));
+        let t: f32 = turbidity;
+        let coefficients: [[f32; 5]; 3] = [
+            [0.1787 * t - 1.4630, -0.3554 * t + 0.4275, -0.0227 * t + 5.3251, 0.1206 * t - 2.5771, -0.0670 * t + 0.3703],
+            [-0.0193 * t - 0.2592, -0.0665 * t + 0.0008, -0.0004 * t + 0.2125, -0.0641 * t - 0.8989, -0.0033 * t + 0.0452],
+            [-0.0167 * t - 0.2608, -0.0950 * t + 0.0092, -0.0079 * t + 0.2102, -0.0441 * t - 1.6537, -0.0109 * t + 0.0529],
+        ];
+        let chi: f32 = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta);
+        let (theta_2, theta_3): (f32, f32) = (theta * theta, theta * theta * theta);
+        let luminance: f32 = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let x: f32 = t * t * (0.00166 * theta_3 - 0.00375 * theta_2 + 0.00209 * theta)
+            + t * (-0.02903 * theta_3 + 0.06377 * theta_2 - 0.03202 * theta + 0.00394)
+            + (0.11693 * theta_3 - 0.21196 * theta_2 + 0.06052 * theta + 0.25886);
+        let y: f32 = t * t * (0.00275 * theta_3 - 0.00610 * theta_2 + 0.00317 * theta)
+            + t * (-0.04214 * theta_3 + 0.08970 * theta_2 - 0.04153 * theta + 0.00516)
+            + (0.15346 * theta_3 - 0.26756 * theta_2 + 0.06670 * theta + 0.26688);
+        let zenith: [f32; 3] = [
+            luminance / distribution(&coefficients[0], 1.0, theta),
+            x / distribution(&coefficients[1], 1.0, theta),
+            y / distribution(&coefficients[2], 1.0, theta),
+        ];
+        Perez { sun, zenith, coefficients, scale }
+    }
+
+    //DIRECTIONS BELOW THE HORIZON SEE THE SKY JUST ABOVE IT
+    fn radiance(&self, direction: &Vector3) -> Colour {
+        let direction: Vector3 = direction.unit_vector();
+        let cos_theta: f32 = f32::max(direction.y, 0.01);
+        let gamma: f32 = f32::acos(Vector3::dot(&direction, &self.sun).clamp(-1.0, 1.0));
+        let [luminance, x, y]: [f32; 3] = [0, 1, 2].map(|i: usize| self.zenith[i] * distribution(&self.coefficients[i], cos_theta, gamma));
+        self.scale * rgb(luminance, x, y)
+    }
+}
+
+//ANALYTIC DAYLIGHT AND A SUN WHOSE IRRADIANCE STAYS THE SAME WHATEVER ITS SIZE, SO A LARGER DISC ONLY SOFTENS THE SHADOWS
+pub struct PhysicalSky {
+    sky: Perez,
+    //1 - COS OF THE ANGULAR RADIUS OF THE SUN
+    sun_versine: f32,
+    sun_radiance: Colour,
+    //CHANCE OF SAMPLING THE SUN RATHER THAN THE TABLE OF THE SKY, BY THEIR POWER
+    sun_probability: f32,
+    //ONLY DRIVES SAMPLING, colour STAYS ANALYTIC
+    table: Environment,
+    power: f32,
+}
+
+impl PhysicalSky {
+    //ANGLES IN RADIANS, AZIMUTH 0 LOOKS TOWARDS -Z AND GROWS TOWARDS +X, THE SUN MUST NOT BE BELOW THE HORIZON
+    pub fn new(elevation: f32, azimuth: f32, turbidity: f32, sun_diameter: f32, intensity: f32) -> PhysicalSky {
+        let sun: Vector3 = V3!(elevation.cos() * azimuth.sin(), elevation.sin(), -elevation.cos() * azimuth.cos());
+        let sky: Perez = Perez::new(sun, turbidity, SCALE * intensity);
+
+        let sun_versine: f32 = versine(0.5 * sun_diameter);
+        let solid_angle: f32 = 2.0 * PI * sun_versine;
+        let irradiance: f32 = SUN_LUMINANCE * 2.0 * PI * versine(SUN_RADIUS);
+        let sun_radiance: Colour = (SCALE * intensity * irradiance / solid_angle) * transmittance(PI / 2.0 - elevation, turbidity);
+
+        let mut image: Framebuffer = Framebuffer::new(TABLE_WIDTH, TABLE_HEIGHT);
+        let mut sky_power: f32 = 0.0;
+        for j in 0..TABLE_HEIGHT {
+            let v: f32 = 1.0 - (j as f32 + 0.5) / TABLE_HEIGHT as f32;
+            let pixel: f32 = (2.0 * PI / TABLE_WIDTH as f32) * (PI / TABLE_HEIGHT as f32) * f32::sin(PI * v);
+            for i in 0..TABLE_WIDTH {
+                let radiance: Colour = sky.radiance(&environment::direction((i as f32 + 0.5) / TABLE_WIDTH as f32, v));
+                image.pixels[j as usize * TABLE_WIDTH as usize + i as usize] = radiance;
+                sky_power += colour::luminance(&radiance) * pixel;
+            }
+        }
+        let sun_power: f32 = colour::luminance(&sun_radiance) * solid_angle;
+        let sun_probability: f32 = if sun_power + sky_power > 0.0 {sun_power / (sun_power + sky_power)} else {0.0};
+
+        PhysicalSky { sky, sun_versine, sun_radiance, sun_probability, table: Environment::new(image, 0.0, 1.0), power: sun_power + sky_power }
+    }
+
+    fn in_sun(&self, direction: &Vector3) -> bool {
+        Vector3::dot(&direction.unit_vector(), &self.sky.sun) >= 1.0 - self.sun_versine
+    }
+}
+
+impl Background for PhysicalSky {
+    fn colour(&self, direction: &Vector3) -> Colour {
+        let sky: Colour = self.sky.radiance(direction);
+        if self.in_sun(direction) {sky + self.sun_radiance} else {sky}
+    }
+
+    fn sample(&self, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        if rng.next_f32() >= self.sun_probability {
+            return self.table.sample(rng);
+        }
+        //UNIFORM OVER THE CONE OF THE DISC
+        let versine: f32 = rng.next_f32() * self.sun_versine;
+        let (cos_theta, sin_theta): (f32, f32) = (1.0 - versine, f32::sqrt(versine * (2.0 - versine)));
+        let phi: f32 = 2.0 * PI * rng.next_f32();
+        let (u, v): (Vector3, Vector3) = Vector3::orthonormal_basis(&self.sky.sun);
+        Some(sin_theta * phi.cos() * u + sin_theta * phi.sin() * v + cos_theta * self.sky.sun)
+    }
+
+    fn pdf_value(&self, direction: &Vector3) -> f32 {
+        let sun: f32 = if self.in_sun(direction) {1.0 / (2.0 * PI * self.sun_versine)} else {0.0};
+        self.sun_probability * sun + (1.0 - self.sun_probability) * self.table.pdf_value(direction)
+    }
+
+    fn power(&self) -> f32 {
+        self.power
+    }
+}
\ No newline at end of file
@@ -0,0 +1,13 @@
+use crate::background::Background;
+use crate::colour::Colour;
+use crate::vector_3::Vector3;
+
+pub struct Solid {
+    pub colour: Colour,
+}
+
+impl Background for Solid {
+    fn colour(&self, _: &Vector3) -> Colour {
+        self.colour
+    }
+}
\ No newline at end of file
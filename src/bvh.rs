@@ -0,0 +1,323 @@
+use crate::aabb::Aabb;
+use crate::hit::{Surface, Hit, HitList};
+use crate::ray::Ray;
+use crate::vector_3::Vector3;
+
+//SURFACE AREA HEURISTIC
+const BINS: usize = 16;
+const TRAVERSAL_COST: f32 = 1.0;
+const INTERSECTION_COST: f32 = 1.0;
+const MIN_LEAF: usize = 2;
+const MAX_LEAF: usize = 8;
+
+type Primitive = (Aabb, Box<dyn Surface>);
+
+pub enum BvhNode {
+    Leaf(Aabb, HitList),
+    Branch(Aabb, Box<BvhNode>, Box<BvhNode>),
+}
+
+impl BvhNode {
+
+    pub fn new(list: HitList) -> BvhNode {
+        let mut bounded: Vec<Primitive> = vec![];
+        let mut unbounded: Vec<Box<dyn Surface>> = vec![];
+
+        //AN INFINITE OR NAN BOX WOULD SPOIL EVERY UNION AND SPLIT IT TOOK PART IN
+        for object in list.objects {
+            match object.bounding_box() {
+                Some(bounds) if bounds.is_finite() => bounded.push((bounds, object)),
+                _ => unbounded.push(object),
+            }
+        }
+
+        let tree: BvhNode = build(bounded);
+        if unbounded.is_empty() {
+            tree
+        } else {
+            BvhNode::Branch(Aabb::INFINITE, Box::new(tree), Box::new(BvhNode::Leaf(Aabb::INFINITE, HitList{ objects: unbounded })))
+        }
+    }
+
+    fn bounds(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf(bounds, _) => bounds,
+            BvhNode::Branch(bounds, _, _) => bounds,
+        }
+    }
+}
+
+fn build(objects: Vec<Primitive>) -> BvhNode {
+
+    let bounds: Aabb = match objects.first() {
+        Some((first, _)) => objects.iter().fold(*first, |bounds: Aabb, (b, _)| Aabb::union(&bounds, b)),
+        None => return BvhNode::Leaf(Aabb::new(Vector3::ZERO, Vector3::ZERO), HitList{ objects: vec![] }),
+    };
+
+    if objects.len() <= MIN_LEAF {
+        return leaf(bounds, objects);
+    }
+
+    let centroid_bounds: Aabb = objects.iter()
+        .fold(Aabb::new(objects[0].0.centroid(), objects[0].0.centroid()),
+            |centroids: Aabb, (b, _)| Aabb::union(&centroids, &Aabb::new(b.centroid(), b.centroid())));
+    let axis: usize = centroid_bounds.longest_axis();
+    let extent: f32 = centroid_bounds.max[axis] - centroid_bounds.min[axis];
+
+    //ALL CENTROIDS COINCIDE, NO PLANE CAN SEPARATE THEM
+    if extent <= 0.0 {
+        if objects.len() <= MAX_LEAF {
+            return leaf(bounds, objects);
+        }
+        return median(bounds, objects, axis);
+    }
+
+    let bin = |b: &Aabb| -> usize {
+        let offset: f32 = (b.centroid()[axis] - centroid_bounds.min[axis]) / extent;
+        usize::min((offset * BINS as f32) as usize, BINS - 1)
+    };
+
+    let mut bins: [(Option<Aabb>, usize); BINS] = [(None, 0); BINS];
+    for (b, _) in objects.iter() {
+        let (bin_bounds, count) = &mut bins[bin(b)];
+        *bin_bounds = Some(bin_bounds.map_or(*b, |bin_bounds: Aabb| Aabb::union(&bin_bounds, b)));
+        *count += 1;
+    }
+
+    //SWEEP FROM THE RIGHT, THEN EVALUATE EVERY PLANE FROM THE LEFT
+    let mut right: [(f32, usize); BINS] = [(0.0, 0); BINS];
+    let mut accumulated: (Option<Aabb>, usize) = (None, 0);
+    for (current, (area, count)) in bins.iter().zip(right.iter_mut()).skip(1).rev() {
+        accumulated = merge(accumulated, *current);
+        *area = accumulated.0.map_or(0.0, |b: Aabb| b.surface_area());
+        *count = accumulated.1;
+    }
+
+    let mut best: (usize, f32) = (0, f32::INFINITY);
+    let mut accumulated: (Option<Aabb>, usize) = (None, 0);
+    for (i, (right_area, right_count)) in right.iter().enumerate().skip(1) {
+        accumulated = merge(accumulated, bins[i - 1]);
+        let left_area: f32 = accumulated.0.map_or(0.0, |b: Aabb| b.surface_area());
+        let cost: f32 = TRAVERSAL_COST + INTERSECTION_COST *
+            (left_area * accumulated.1 as f32 + right_area * *right_count as f32) / bounds.surface_area();
+        if cost < best.1 {
+            best = (i, cost);
+        }
+    }
+
+    let leaf_cost: f32 = INTERSECTION_COST * objects.len() as f32;
+    if best.1 >= leaf_cost && objects.len() <= MAX_LEAF {
+        return leaf(bounds, objects);
+    }
+    //AREAS TOO LARGE TO COMPARE
+    if !best.1.is_finite() {
+        return median(bounds, objects, axis);
+    }
+
+    let (left, right): (Vec<Primitive>, Vec<Primitive>) = objects.into_iter().partition(|(b, _)| bin(b) < best.0);
+    if left.is_empty() || right.is_empty() {
+        return median(bounds, left.into_iter().chain(right).collect(), axis);
+    }
+    branch(bounds, left, right)
+}
+
+//HALF THE OBJECTS EITHER SIDE, ORDERED BY THEIR CENTROIDS ALONG axis, FOR WHEN NO PLANE SEPARATES THEM
+fn median(bounds: Aabb, mut objects: Vec<Primitive>, axis: usize) -> BvhNode {
+    objects.sort_by(|(a, _), (b, _)| a.centroid()[axis].total_cmp(&b.centroid()[axis]));
+    let right: Vec<Primitive> = objects.split_off(objects.len() / 2);
+    branch(bounds, objects, right)
+}
+
+fn merge(a: (Option<Aabb>, usize), b: (Option<Aabb>, usize)) -> (Option<Aabb>, usize) {
+    let bounds: Option<Aabb> = match (a.0, b.0) {
+        (Some(a), Some(b)) => Some(Aabb::union(&a, &b)),
+        (a, b) => a.or(b),
+    };
+    (bounds, a.1 + b.1)
+}
+
+fn leaf(bounds: Aabb, objects: Vec<Primitive>) -> BvhNode {
+    BvhNode::Leaf(bounds, HitList{ objects: objects.into_iter().map(|(_, object)| object).collect() })
+}
+
+fn branch(bounds: Aabb, left: Vec<Primitive>, right: Vec<Primitive>) -> BvhNode {
+    BvhNode::Branch(bounds, Box::new(build(left)), Box::new(build(right)))
+}
+
+impl Surface for BvhNode {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        if !self.bounds().hit(ray, min, max) {
+            return None;
+        }
+
+        match self {
+            BvhNode::Leaf(_, list) => list.hit(ray, min, max),
+            BvhNode::Branch(_, left, right) => {
+                match left.hit(ray, min, max) {
+                    Some(hit) => right.hit(ray, min, hit.distance).or(Some(hit)),
+                    None => right.hit(ray, min, max),
+                }
+            }
+        }
+    }
+
+    fn hit_random(&self, ray: &Ray, min: f32, max: f32, rng: &mut xorshift::StdRng) -> Option<Hit> {
+        if !self.bounds().hit(ray, min, max) {
+            return None;
+        }
+
+        match self {
+            BvhNode::Leaf(_, list) => list.hit_random(ray, min, max, rng),
+            BvhNode::Branch(_, left, right) => {
+                match left.hit_random(ray, min, max, rng) {
+                    Some(hit) => right.hit_random(ray, min, hit.distance, rng).or(Some(hit)),
+                    None => right.hit_random(ray, min, max, rng),
+                }
+            }
+        }
+    }
+
+    //THE UNBOUNDED OBJECTS HANG OFF AN INFINITE BOX
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bounds: Aabb = *self.bounds();
+        if bounds.is_finite() {Some(bounds)} else {None}
+    }
+
+    fn is_closed(&self) -> bool {
+        match self {
+            BvhNode::Leaf(_, list) => list.is_closed(),
+            BvhNode::Branch(_, left, right) => left.is_closed() && right.is_closed(),
+        }
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        if !self.bounds().hit(ray, f32::NEG_INFINITY, f32::INFINITY) {
+            return vec![];
+        }
+
+        match self {
+            BvhNode::Leaf(_, list) => list.crossings(ray),
+            BvhNode::Branch(_, left, right) => {
+                let mut crossings: Vec<Hit> = left.crossings(ray);
+                crossings.extend(right.crossings(ray));
+                crossings
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use xorshift::Rng;
+
+    use super::*;
+    use crate::geometry::plane::Plane;
+    use crate::geometry::sphere::Sphere;
+    use crate::material::Material;
+    use crate::material::lambertian::Lambertian;
+    use crate::texture::solid_colour::SolidColour;
+
+    fn grey() -> Arc<dyn Material> {
+        Arc::new(Lambertian { albedo: Arc::new(SolidColour { colour: V3!(0.5, 0.5, 0.5) }) })
+    }
+
+    fn rng(seed: usize) -> xorshift::StdRng {
+        xorshift::SeedableRng::from_seed(&[seed, 2, 3, 4][..])
+    }
+
+    //A SPHERE THAT REPORTS WHATEVER BOX IT IS GIVEN
+    struct Boxed {
+        sphere: Sphere,
+        bounds: Aabb,
+    }
+
+    impl Surface for Boxed {
+        fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+            self.sphere.hit(ray, min, max)
+        }
+
+        fn bounding_box(&self) -> Option<Aabb> {
+            Some(self.bounds)
+        }
+    }
+
+    fn spheres(count: usize, rng: &mut xorshift::StdRng) -> Vec<Sphere> {
+        (0..count).map(|_| Sphere { center: Vector3::random_between(-10.0, 10.0, rng), radius: 0.2 + rng.next_f32(), material: grey() }).collect()
+    }
+
+    //THE CLOSEST HIT OF EVERY RAY AGREES WITH A PLAIN LIST OF THE SAME OBJECTS
+    fn assert_matches(bvh: &BvhNode, list: &HitList, rng: &mut xorshift::StdRng) {
+        for _ in 0..2000 {
+            let ray: Ray = Ray { origin: Vector3::random_between(-15.0, 15.0, rng), direction: Vector3::random_unit_vector(rng), time: 0.0, differentials: None };
+            let expected: Option<f32> = list.hit(&ray, 0.001, f32::INFINITY).map(|hit: Hit| hit.distance);
+            let found: Option<f32> = bvh.hit(&ray, 0.001, f32::INFINITY).map(|hit: Hit| hit.distance);
+            assert_eq!(found, expected, "ray from {:?} along {:?}", ray.origin, ray.direction);
+        }
+    }
+
+    fn copies(spheres: &[Sphere]) -> (HitList, HitList) {
+        let copy = |sphere: &Sphere| -> Box<dyn Surface> { Box::new(Sphere { center: sphere.center, radius: sphere.radius, material: grey() }) };
+        (HitList { objects: spheres.iter().map(copy).collect() }, HitList { objects: spheres.iter().map(copy).collect() })
+    }
+
+    #[test]
+    fn hits_match_a_linear_scan() {
+        let mut rng: xorshift::StdRng = rng(1);
+        let (list, objects): (HitList, HitList) = copies(&spheres(300, &mut rng));
+        assert_matches(&BvhNode::new(objects), &list, &mut rng);
+    }
+
+    #[test]
+    fn coincident_centroids_split_down_to_small_leaves() {
+        let mut rng: xorshift::StdRng = rng(2);
+        let spheres: Vec<Sphere> = (0..5 * MAX_LEAF).map(|index: usize| Sphere { center: Vector3::ZERO, radius: 1.0 + index as f32, material: grey() }).collect();
+        let (list, objects): (HitList, HitList) = copies(&spheres);
+        assert_matches(&BvhNode::new(objects), &list, &mut rng);
+    }
+
+    #[test]
+    fn non_finite_boxes_are_kept_apart_from_the_tree() {
+        let mut rng: xorshift::StdRng = rng(3);
+        let spheres: Vec<Sphere> = spheres(3 * MAX_LEAF, &mut rng);
+        let (list, _): (HitList, HitList) = copies(&spheres);
+        let nan: Vector3 = V3!(f32::NAN, f32::NAN, f32::NAN);
+        let objects: Vec<Box<dyn Surface>> = spheres.into_iter().enumerate().map(|(index, sphere): (usize, Sphere)| -> Box<dyn Surface> {
+            let bounds: Aabb = match index % 3 {
+                0 => Aabb { min: nan, max: nan },
+                1 => Aabb::INFINITE,
+                _ => Aabb::new(sphere.center - sphere.radius * Vector3::ONE, sphere.center + sphere.radius * Vector3::ONE),
+            };
+            Box::new(Boxed { sphere, bounds })
+        }).collect();
+        let bvh: BvhNode = BvhNode::new(HitList { objects });
+        assert!(bvh.bounding_box().is_none());
+        assert_matches(&bvh, &list, &mut rng);
+    }
+
+    #[test]
+    fn boxes_too_large_to_compare_still_split() {
+        let mut rng: xorshift::StdRng = rng(4);
+        let spheres: Vec<Sphere> = spheres(3 * MAX_LEAF, &mut rng);
+        let (list, _): (HitList, HitList) = copies(&spheres);
+        //SURFACE AREAS OVERFLOW, SO EVERY SPLIT COSTS INFINITY OR NAN
+        let objects: Vec<Box<dyn Surface>> = spheres.into_iter().map(|sphere: Sphere| -> Box<dyn Surface> {
+            let bounds: Aabb = Aabb::new(sphere.center - 1e30 * Vector3::ONE, sphere.center + 1e30 * Vector3::ONE);
+            Box::new(Boxed { sphere, bounds })
+        }).collect();
+        assert_matches(&BvhNode::new(HitList { objects }), &list, &mut rng);
+    }
+
+    #[test]
+    fn unbounded_objects_leave_the_tree_unbounded() {
+        let plane: Plane = Plane { point: Vector3::ZERO, normal: V3!(0.0, 1.0, 0.0), material: grey() };
+        let sphere: Sphere = Sphere { center: V3!(0.0, 5.0, 0.0), radius: 1.0, material: grey() };
+        assert!(BvhNode::new(HitList { objects: vec![Box::new(sphere)] }).bounding_box().is_some());
+        let sphere: Sphere = Sphere { center: V3!(0.0, 5.0, 0.0), radius: 1.0, material: grey() };
+        let bvh: BvhNode = BvhNode::new(HitList { objects: vec![Box::new(sphere), Box::new(plane)] });
+        assert!(bvh.bounding_box().is_none());
+        let ray: Ray = Ray { origin: V3!(100.0, 1.0, 0.0), direction: V3!(0.0, -1.0, 0.0), time: 0.0, differentials: None };
+        assert!((bvh.hit(&ray, 0.001, f32::INFINITY).unwrap().distance - 1.0).abs() < 1e-4);
+    }
+}
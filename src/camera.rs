@@ -1,47 +1,57 @@
-use crate::vector_3::{Vector3, Point3};
-use crate::ray::Ray;
-
-pub struct Camera {
-    pub origin: Point3,
-    pub horizontal: Vector3,
-    pub vertical: Vector3,
-    pub up: Point3,
-    pub right: Point3,
-    pub forward: Point3,
-    pub focus_distance: f32,
-    pub lens_radius: f32,
-}
+use xorshift::Rng;
 
-impl Camera {
+use crate::geometry::frame::Frame;
+use crate::ray::{Ray, Differentials};
+use crate::vector_3::{Vector3, Point3};
 
-    pub fn new(origin: Point3, target: Point3, up: Vector3, vertical_fov: f32, aspect_ratio: f32, aperture: f32, focus_distance: f32) -> Camera {
-        let theta: f32 = f32::to_radians(vertical_fov);
-        let h: f32 = f32::tan(theta/2.0);
-        let viewport_height: f32 = 2.0 * h;
-        let viewport_width: f32 = aspect_ratio * viewport_height;
+pub mod perspective;
+pub mod orthographic;
+pub mod fisheye;
+pub mod equirectangular;
+pub mod cylindrical;
 
-        let forward: Vector3 = Vector3::unit_vector(&(origin - target));
-        let right: Vector3 = Vector3::unit_vector(&Vector3::cross(&up, &forward));
-        let up: Vector3 = Vector3::cross(&forward, &right);
+//TURNS A POSITION ON THE IMAGE INTO A RAY
+pub trait Camera: Send + Sync {
+    //s AND t GO FROM 0 TO 1 ACROSS THE IMAGE FROM THE LOWER LEFT, step IS THE SIZE OF A PIXEL IN s AND t, FOR THE DIFFERENTIALS
+    //NONE WHERE THE PROJECTION LEAVES THE IMAGE EMPTY
+    fn get_ray(&self, s: f32, t: f32, step: (f32, f32), rng: &mut xorshift::StdRng) -> Option<Ray>;
 
-        let horizontal: Vector3 = focus_distance * viewport_width * right;
-        let vertical: Vector3 = focus_distance * viewport_height * up;
-        let lens_radius: f32 = aperture / 2.0;
-        Camera { origin, up, right, forward, horizontal, vertical, focus_distance, lens_radius }
-    }
+    //PROJECTIONS THAT COVER FIXED ANGLES WHATEVER THE SHAPE OF THE IMAGE IGNORE IT
+    fn set_aspect_ratio(&mut self, _aspect_ratio: f32) {}
+}
 
-    pub fn get_ray(&self, s: f32, t: f32, rng: &mut xorshift::StdRng) -> Ray {
+//u POINTS RIGHT, v UP AND w BACK FROM target TOWARDS origin
+pub fn look_at(origin: Point3, target: Point3, up: &Vector3) -> Frame {
+    let forward: Vector3 = Vector3::unit_vector(&(origin - target));
+    let right: Vector3 = Vector3::unit_vector(&Vector3::cross(up, &forward));
+    let up: Vector3 = Vector3::cross(&forward, &right);
+    Frame { origin, u: right, v: up, w: forward }
+}
 
-        let radius: Vector3 = self.lens_radius * Vector3::random_in_disk(rng);
-        let offset: Vector3 = self.right * radius.x + self.up * radius.y;
+//RAYS ARE SPREAD UNIFORMLY OVER THE TIME THE SHUTTER IS OPEN
+#[derive(Copy, Clone, Default)]
+pub struct Shutter {
+    pub open: f32,
+    pub close: f32,
+}
 
-        Ray{
-            origin: self.origin + offset,
-            direction: self.lower_left_corner() + s * self.horizontal + t * self.vertical - self.origin - offset
+impl Shutter {
+    pub fn time(&self, rng: &mut xorshift::StdRng) -> f32 {
+        if self.close > self.open {
+            self.open + rng.next_f32() * (self.close - self.open)
+        } else {
+            self.open
         }
     }
+}
 
-    fn lower_left_corner(&self) -> Vector3 {
-        self.origin - self.horizontal/2.0 - self.vertical/2.0 - self.focus_distance * self.forward
-    } 
+//FOR PROJECTIONS THROUGH A SINGLE POINT, direction GIVES THE LOCAL DIRECTION FOR s AND t
+//THE DIFFERENTIALS ARE DROPPED WHEN A NEIGHBOURING PIXEL IS EMPTY
+pub fn central_ray(frame: &Frame, direction: impl Fn(f32, f32) -> Option<Vector3>, s: f32, t: f32, step: (f32, f32), time: f32) -> Option<Ray> {
+    let centre: Vector3 = direction(s, t)?;
+    let differentials: Option<Differentials> = match (direction(s + step.0, t), direction(s, t + step.1)) {
+        (Some(x), Some(y)) => Some(Differentials { origin: [frame.origin; 2], direction: [frame.to_world_vector(&x), frame.to_world_vector(&y)] }),
+        _ => None,
+    };
+    Some(Ray { origin: frame.origin, direction: frame.to_world_vector(&centre), time, differentials })
 }
\ No newline at end of file
@@ -0,0 +1,32 @@
+use std::f32::consts::PI;
+
+use crate::camera::{Camera, Shutter, central_ray};
+use crate::geometry::frame::Frame;
+use crate::vector_3::Vector3;
+use crate::ray::Ray;
+
+//ALL THE WAY ROUND ACROSS THE IMAGE, A PERSPECTIVE vertical_fov UP AND DOWN, SO VERTICAL LINES STAY STRAIGHT
+pub struct Cylindrical {
+    pub frame: Frame,
+    //HEIGHT OF THE IMAGE ON A CYLINDER OF RADIUS 1
+    pub height: f32,
+    pub shutter: Shutter,
+}
+
+impl Cylindrical {
+    //vertical_fov IN DEGREES
+    pub fn new(frame: Frame, vertical_fov: f32, shutter: Shutter) -> Cylindrical {
+        Cylindrical { frame, height: 2.0 * f32::tan(f32::to_radians(vertical_fov) / 2.0), shutter }
+    }
+}
+
+impl Camera for Cylindrical {
+
+    fn get_ray(&self, s: f32, t: f32, step: (f32, f32), rng: &mut xorshift::StdRng) -> Option<Ray> {
+        let direction = |s: f32, t: f32| -> Option<Vector3> {
+            let (sin_phi, cos_phi): (f32, f32) = f32::sin_cos((s - 0.5) * 2.0 * PI);
+            Some(V3!(sin_phi, (t - 0.5) * self.height, -cos_phi))
+        };
+        central_ray(&self.frame, direction, s, t, step, self.shutter.time(rng))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+use std::f32::consts::PI;
+
+use crate::camera::{Camera, Shutter, central_ray};
+use crate::geometry::frame::Frame;
+use crate::vector_3::Vector3;
+use crate::ray::Ray;
+
+//EVERY DIRECTION, LONGITUDE ACROSS AND LATITUDE UP, THE VIEW IN THE MIDDLE, 2:1 IMAGES ARE UNSTRETCHED
+pub struct Equirectangular {
+    pub frame: Frame,
+    pub shutter: Shutter,
+}
+
+fn direction(s: f32, t: f32) -> Option<Vector3> {
+    let (sin_longitude, cos_longitude): (f32, f32) = f32::sin_cos((s - 0.5) * 2.0 * PI);
+    let (sin_latitude, cos_latitude): (f32, f32) = f32::sin_cos((t - 0.5) * PI);
+    Some(V3!(cos_latitude * sin_longitude, sin_latitude, -cos_latitude * cos_longitude))
+}
+
+impl Camera for Equirectangular {
+
+    fn get_ray(&self, s: f32, t: f32, step: (f32, f32), rng: &mut xorshift::StdRng) -> Option<Ray> {
+        central_ray(&self.frame, direction, s, t, step, self.shutter.time(rng))
+    }
+}
\ No newline at end of file
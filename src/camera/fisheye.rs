@@ -0,0 +1,58 @@
+use crate::camera::{Camera, Shutter, central_ray};
+use crate::geometry::frame::Frame;
+use crate::vector_3::Vector3;
+use crate::ray::Ray;
+
+//HOW THE DISTANCE FROM THE CENTRE OF THE CIRCLE GROWS WITH THE ANGLE FROM THE VIEW
+#[derive(Copy, Clone)]
+pub enum Mapping {
+    //PROPORTIONAL TO THE ANGLE
+    Equidistant,
+    //PRESERVES SOLID ANGLE, PROPORTIONAL TO THE SINE OF HALF THE ANGLE
+    Equisolid,
+}
+
+//A CIRCLE AS TALL AS THE IMAGE COVERING fov, NOTHING OUTSIDE IT
+pub struct Fisheye {
+    pub frame: Frame,
+    pub mapping: Mapping,
+    //HALF OF fov, RADIANS
+    pub max_angle: f32,
+    pub aspect_ratio: f32,
+    pub shutter: Shutter,
+}
+
+impl Fisheye {
+    //fov IN DEGREES, UP TO 360
+    pub fn new(frame: Frame, mapping: Mapping, fov: f32, aspect_ratio: f32, shutter: Shutter) -> Fisheye {
+        Fisheye { frame, mapping, max_angle: f32::to_radians(fov) / 2.0, aspect_ratio, shutter }
+    }
+
+    fn direction(&self, s: f32, t: f32) -> Option<Vector3> {
+        let (x, y): (f32, f32) = ((2.0 * s - 1.0) * self.aspect_ratio, 2.0 * t - 1.0);
+        let r: f32 = f32::sqrt(x * x + y * y);
+        if r > 1.0 {
+            return None;
+        }
+        let theta: f32 = match self.mapping {
+            Mapping::Equidistant => r * self.max_angle,
+            Mapping::Equisolid => 2.0 * f32::asin(f32::min(1.0, r * f32::sin(self.max_angle / 2.0))),
+        };
+        if r == 0.0 {
+            return Some(V3!(0.0, 0.0, -1.0));
+        }
+        let (sin_theta, cos_theta): (f32, f32) = theta.sin_cos();
+        Some(V3!(sin_theta * x / r, sin_theta * y / r, -cos_theta))
+    }
+}
+
+impl Camera for Fisheye {
+
+    fn get_ray(&self, s: f32, t: f32, step: (f32, f32), rng: &mut xorshift::StdRng) -> Option<Ray> {
+        central_ray(&self.frame, |s: f32, t: f32| self.direction(s, t), s, t, step, self.shutter.time(rng))
+    }
+
+    fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use crate::camera::{Camera, Shutter};
+use crate::geometry::frame::Frame;
+use crate::vector_3::{Vector3, Point3};
+use crate::ray::{Ray, Differentials};
+
+//PARALLEL RAYS FROM A RECTANGLE height UNITS TALL CENTRED ON THE ORIGIN OF THE FRAME
+pub struct Orthographic {
+    pub frame: Frame,
+    pub width: f32,
+    pub height: f32,
+    pub shutter: Shutter,
+}
+
+impl Orthographic {
+    pub fn new(frame: Frame, height: f32, aspect_ratio: f32, shutter: Shutter) -> Orthographic {
+        Orthographic { frame, width: aspect_ratio * height, height, shutter }
+    }
+}
+
+impl Camera for Orthographic {
+
+    fn get_ray(&self, s: f32, t: f32, step: (f32, f32), rng: &mut xorshift::StdRng) -> Option<Ray> {
+        let origin = |s: f32, t: f32| -> Point3 { self.frame.origin + (s - 0.5) * self.width * self.frame.u + (t - 0.5) * self.height * self.frame.v };
+        let direction: Vector3 = -self.frame.w;
+        Some(Ray{
+            origin: origin(s, t),
+            direction,
+            time: self.shutter.time(rng),
+            differentials: Some(Differentials { origin: [origin(s + step.0, t), origin(s, t + step.1)], direction: [direction; 2] }),
+        })
+    }
+
+    fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.width = aspect_ratio * self.height;
+    }
+}
\ No newline at end of file
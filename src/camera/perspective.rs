@@ -0,0 +1,56 @@
+use crate::camera::{Camera, Shutter};
+use crate::geometry::frame::Frame;
+use crate::vector_3::{Vector3, Point3};
+use crate::ray::{Ray, Differentials};
+
+//THIN LENS FOCUSED focus_distance AWAY, A PINHOLE WITHOUT AN APERTURE
+pub struct Perspective {
+    pub frame: Frame,
+    pub horizontal: Vector3,
+    pub vertical: Vector3,
+    pub focus_distance: f32,
+    pub lens_radius: f32,
+    pub shutter: Shutter,
+}
+
+impl Perspective {
+
+    pub fn new(frame: Frame, vertical_fov: f32, aspect_ratio: f32, aperture: f32, focus_distance: f32, shutter: Shutter) -> Perspective {
+        let theta: f32 = f32::to_radians(vertical_fov);
+        let h: f32 = f32::tan(theta/2.0);
+        let viewport_height: f32 = 2.0 * h;
+        let viewport_width: f32 = aspect_ratio * viewport_height;
+
+        let horizontal: Vector3 = focus_distance * viewport_width * frame.u;
+        let vertical: Vector3 = focus_distance * viewport_height * frame.v;
+        let lens_radius: f32 = aperture / 2.0;
+        Perspective { frame, horizontal, vertical, focus_distance, lens_radius, shutter }
+    }
+
+    fn lower_left_corner(&self) -> Vector3 {
+        self.frame.origin - self.horizontal/2.0 - self.vertical/2.0 - self.focus_distance * self.frame.w
+    }
+}
+
+impl Camera for Perspective {
+
+    fn get_ray(&self, s: f32, t: f32, step: (f32, f32), rng: &mut xorshift::StdRng) -> Option<Ray> {
+
+        let radius: Vector3 = self.lens_radius * Vector3::random_in_disk(rng);
+        let offset: Vector3 = self.frame.u * radius.x + self.frame.v * radius.y;
+        let time: f32 = self.shutter.time(rng);
+
+        let origin: Point3 = self.frame.origin + offset;
+        let direction = |s: f32, t: f32| self.lower_left_corner() + s * self.horizontal + t * self.vertical - self.frame.origin - offset;
+        Some(Ray{
+            origin,
+            direction: direction(s, t),
+            time,
+            differentials: Some(Differentials { origin: [origin; 2], direction: [direction(s + step.0, t), direction(s, t + step.1)] }),
+        })
+    }
+
+    fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.horizontal = aspect_ratio * self.vertical.length() * self.frame.u;
+    }
+}
\ No newline at end of file
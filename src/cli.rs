@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+
+use crate::colour::Transfer;
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum BuiltinScene {
+    RandomSpheres,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum OutputFormat {
+    /// Binary P6 PPM
+    Ppm,
+    /// ASCII P3 PPM
+    PpmAscii,
+    /// 8-bit PNG
+    Png,
+    /// 16-bit PNG
+    Png16,
+    /// Linear 32-bit float Portable Float Map
+    Pfm,
+    /// Linear Radiance RGBE
+    Hdr,
+    /// Linear OpenEXR with half channels
+    Exr,
+    /// Linear OpenEXR with float channels
+    Exr32,
+}
+
+impl OutputFormat {
+    pub fn from_extension(path: &Path) -> OutputFormat {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("png") => OutputFormat::Png,
+            Some("pfm") => OutputFormat::Pfm,
+            Some("hdr") => OutputFormat::Hdr,
+            Some("exr") => OutputFormat::Exr,
+            _ => OutputFormat::Ppm,
+        }
+    }
+}
+
+fn parse_transfer(value: &str) -> Result<Transfer, String> {
+    match value {
+        "linear" => Ok(Transfer::Linear),
+        "srgb" => Ok(Transfer::Srgb),
+        gamma => match gamma.parse::<f32>() {
+            Ok(gamma) if gamma > 0.0 => Ok(Transfer::Gamma(gamma)),
+            _ => Err(format!("expected 'linear', 'srgb' or a positive gamma, found '{}'", value)),
+        },
+    }
+}
+
+//SETTINGS LEFT UNSET FALL BACK TO THE ONES STORED IN THE SCENE
+#[derive(Parser)]
+#[command(version, about = "A basic path tracer")]
+pub struct Arguments {
+    /// Scene description file (TOML), takes precedence over --scene
+    pub scene_file: Option<PathBuf>,
+
+    /// Built-in scene to render when no scene file is given
+    #[arg(long, value_enum, default_value_t = BuiltinScene::RandomSpheres)]
+    pub scene: BuiltinScene,
+
+    /// Image width in pixels, keeps the scene aspect ratio unless --height is also given
+    #[arg(long, value_parser = clap::value_parser!(u16).range(2..))]
+    pub width: Option<u16>,
+
+    /// Image height in pixels, keeps the scene aspect ratio unless --width is also given
+    #[arg(long, value_parser = clap::value_parser!(u16).range(2..))]
+    pub height: Option<u16>,
+
+    /// Samples per pixel
+    #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+    pub samples: Option<u16>,
+
+    /// Maximum number of bounces per path
+    #[arg(short, long)]
+    pub depth: Option<u16>,
+
+    /// Comma separated seed for the random number generator
+    #[arg(long, value_delimiter = ',', num_args = 1.., default_values_t = [1, 2, 3, 4])]
+    pub seed: Vec<usize>,
+
+    /// Output file, '-' writes to standard output
+    #[arg(short, long, default_value = "-")]
+    pub output: PathBuf,
+
+    /// Output image format, inferred from the output extension when omitted
+    #[arg(short, long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Transfer function applied before quantising: 'linear', 'srgb' or a gamma exponent, floating point formats stay linear
+    #[arg(long, value_parser = parse_transfer, default_value = "2")]
+    pub transfer: Transfer,
+
+    /// Worker threads, defaults to one per core
+    #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+    pub threads: Option<u16>,
+
+    /// Do not report progress on standard error
+    #[arg(short, long)]
+    pub quiet: bool,
+}
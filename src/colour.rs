@@ -1,4 +1,5 @@
 use std::f32::consts::PI;
+use std::io::{self, Write};
 use crate::vector_3::Vector3;
 
 pub type Colour = Vector3;
@@ -24,11 +25,48 @@ pub fn hsv2rgb(hsv: &Colour) -> Colour {
     } 
 }
 
-pub fn write_colour(pixel_colour: &Colour) -> () {
+//ENCODING APPLIED TO LINEAR RADIANCE BEFORE QUANTISATION
+#[derive(Copy, Clone)]
+pub enum Transfer {
+    Linear,
+    Gamma(f32),
+    Srgb,
+}
+
+impl Transfer {
+    pub fn encode(&self, value: f32) -> f32 {
+        let value: f32 = f32::max(value, 0.0);
+        match self {
+            Transfer::Linear => value,
+            Transfer::Gamma(gamma) => value.powf(1.0 / gamma),
+            Transfer::Srgb => if value <= 0.0031308 {12.92 * value} else {1.055 * value.powf(1.0 / 2.4) - 0.055},
+        }
+    }
+
+    pub fn decode(&self, value: f32) -> f32 {
+        let value: f32 = f32::max(value, 0.0);
+        match self {
+            Transfer::Linear => value,
+            Transfer::Gamma(gamma) => value.powf(*gamma),
+            Transfer::Srgb => if value <= 0.04045 {value / 12.92} else {((value + 0.055) / 1.055).powf(2.4)},
+        }
+    }
+}
+
+//RELATIVE LUMINANCE OF LINEAR REC. 709 PRIMARIES
+pub fn luminance(colour: &Colour) -> f32 {
+    Vector3::dot(colour, &V3!(0.2126, 0.7152, 0.0722))
+}
+
+pub fn quantise_8(pixel_colour: &Colour, transfer: Transfer) -> [u8; 3] {
+    [pixel_colour.x, pixel_colour.y, pixel_colour.z].map(|value: f32| (256.0 * transfer.encode(value).clamp(0.0, 0.999)) as u8)
+}
+
+pub fn quantise_16(pixel_colour: &Colour, transfer: Transfer) -> [u16; 3] {
+    [pixel_colour.x, pixel_colour.y, pixel_colour.z].map(|value: f32| (65535.0 * transfer.encode(value).clamp(0.0, 1.0) + 0.5) as u16)
+}
 
-    let pixel_colour: Colour = *pixel_colour;
-    println!("{} {} {}",
-        (256.0 * pixel_colour.x.sqrt().clamp(0.0, 0.999)) as u8,
-        (256.0 * pixel_colour.y.sqrt().clamp(0.0, 0.999)) as u8,
-        (256.0 * pixel_colour.z.sqrt().clamp(0.0, 0.999)) as u8);
+pub fn write_colour(out: &mut impl Write, pixel_colour: &Colour, transfer: Transfer) -> io::Result<()> {
+    let [r, g, b] = quantise_8(pixel_colour, transfer);
+    writeln!(out, "{} {} {}", r, g, b)
 }
\ No newline at end of file
@@ -0,0 +1,39 @@
+//PIECEWISE CONSTANT DISTRIBUTION OVER INDICES, PROPORTIONAL TO NON-NEGATIVE WEIGHTS, UNIFORM WHEN THEY ARE ALL ZERO
+pub struct Distribution {
+    //n + 1 ENTRIES FROM 0 TO 1
+    cdf: Vec<f32>,
+    pub total: f32,
+}
+
+impl Distribution {
+    pub fn new(weights: &[f32]) -> Distribution {
+        let mut sums: Vec<f64> = Vec::with_capacity(weights.len() + 1);
+        sums.push(0.0);
+        for weight in weights {
+            sums.push(sums[sums.len() - 1] + f32::max(*weight, 0.0) as f64);
+        }
+        let total: f64 = sums[weights.len()];
+        let cdf: Vec<f32> = if total > 0.0 {
+            sums.iter().map(|sum: &f64| (sum / total) as f32).collect()
+        } else {
+            (0..=weights.len()).map(|i: usize| i as f32 / weights.len() as f32).collect()
+        };
+        Distribution { cdf, total: total as f32 }
+    }
+
+    fn len(&self) -> usize {
+        self.cdf.len() - 1
+    }
+
+    //INDEX FOR u IN [0, 1), AND WHERE u FELL INSIDE ITS INTERVAL SO IT CAN BE REUSED
+    pub fn sample(&self, u: f32) -> (usize, f32) {
+        let index: usize = usize::min(self.cdf[1..].partition_point(|c: &f32| *c <= u), self.len() - 1);
+        let (low, high): (f32, f32) = (self.cdf[index], self.cdf[index + 1]);
+        let offset: f32 = if high > low {(u - low) / (high - low)} else {0.0};
+        (index, offset.clamp(0.0, 1.0 - f32::EPSILON))
+    }
+
+    pub fn probability(&self, index: usize) -> f32 {
+        self.cdf[index + 1] - self.cdf[index]
+    }
+}
\ No newline at end of file
@@ -1 +1,13 @@
-pub mod sphere;
\ No newline at end of file
+pub mod sphere;
+pub mod triangle;
+pub mod transformed;
+pub mod constant_medium;
+pub mod frame;
+pub mod plane;
+pub mod quad;
+pub mod disk;
+pub mod cuboid;
+pub mod cylinder;
+pub mod cone;
+pub mod torus;
+pub mod csg;
\ No newline at end of file
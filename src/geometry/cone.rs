@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::geometry::disk::{disk_bounds, intersect_cap};
+use crate::geometry::frame::Frame;
+use crate::geometry::sphere::{solve_quadratic, QuadraticSolution};
+use crate::hit::{Surface, Hit};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+//CLOSED AT THE BASE, UVS AS FOR THE CYLINDER
+pub struct Cone {
+    pub base: Point3,
+    pub apex: Point3,
+    pub radius: f32,
+    pub material: Arc<dyn Material>
+}
+
+impl Cone {
+    //HITS ON THE SIDE AND THE BASE, AS DISTANCE, LOCAL POINT AND LOCAL NORMAL
+    fn candidates(&self, frame: &Frame, ray: &Ray, min: f32, max: f32) -> Vec<(f32, Vector3, Vector3)> {
+        let height: f32 = (self.apex - self.base).length();
+        let origin: Vector3 = frame.to_local(&ray.origin);
+        let direction: Vector3 = frame.to_local_vector(&ray.direction);
+        let mut candidates: Vec<(f32, Vector3, Vector3)> = Vec::with_capacity(3);
+
+        //x^2 + y^2 = (k (H - z))^2
+        let k: f32 = self.radius / height;
+        let h: f32 = height - origin.z;
+        let a: f32 = direction.x * direction.x + direction.y * direction.y - k * k * direction.z * direction.z;
+        let b: f32 = 2.0 * (origin.x * direction.x + origin.y * direction.y + k * k * h * direction.z);
+        let c: f32 = origin.x * origin.x + origin.y * origin.y - k * k * h * h;
+        let roots: Vec<f32> = if a.abs() > 1e-12 {
+            match solve_quadratic(a, b, c) {
+                QuadraticSolution::Result(one, two) => vec![one, two],
+                QuadraticSolution::None => vec![],
+            }
+        } else if b.abs() > 1e-12 {
+            //RAY PARALLEL TO THE SLANT, ONLY ONE CROSSING
+            vec![-c / b]
+        } else {
+            vec![]
+        };
+        for t in roots {
+            let local: Vector3 = origin + t * direction;
+            if min < t && t < max && (0.0..=height).contains(&local.z) {
+                let r: f32 = k * (height - local.z);
+                candidates.push((t, local, V3!(local.x, local.y, k * r).unit_vector()));
+            }
+        }
+        if let Some((t, local)) = intersect_cap(frame, 0.0, self.radius, ray, min, max) {
+            candidates.push((t, local, -Vector3::W));
+        }
+        candidates
+    }
+
+    fn at(&self, frame: &Frame, ray: &Ray, (t, local, n): (f32, Vector3, Vector3)) -> Hit {
+        let height: f32 = (self.apex - self.base).length();
+        let uv: (f32, f32) = if n.z < 0.0 {
+            (Frame::azimuth(&local), f32::sqrt(local.x * local.x + local.y * local.y) / self.radius)
+        } else {
+            (Frame::azimuth(&local), local.z / height)
+        };
+        Hit::new(ray, t, frame.to_world_vector(&n), self.material.clone(), uv, frame.azimuthal(&local))
+    }
+}
+
+impl Surface for Cone {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let frame: Frame = Frame::new(self.base, &(self.apex - self.base));
+        let candidate: (f32, Vector3, Vector3) = self.candidates(&frame, ray, min, max).into_iter().min_by(|a, b| a.0.total_cmp(&b.0))?;
+        Some(self.at(&frame, ray, candidate))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let padding: Vector3 = 0.0001 * Vector3::ONE;
+        let apex: Aabb = Aabb::new(self.apex - padding, self.apex + padding);
+        Some(Aabb::union(&disk_bounds(&self.base, &(self.apex - self.base), self.radius), &apex))
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        let frame: Frame = Frame::new(self.base, &(self.apex - self.base));
+        self.candidates(&frame, ray, f32::NEG_INFINITY, f32::INFINITY).into_iter()
+            .map(|candidate| self.at(&frame, ray, candidate))
+            .collect()
+    }
+}
\ No newline at end of file
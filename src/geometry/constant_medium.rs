@@ -0,0 +1,128 @@
+use std::sync::Arc;
+use xorshift::Rng;
+
+use crate::aabb::Aabb;
+use crate::hit::{Surface, Hit};
+use crate::medium::Medium;
+use crate::ray::Ray;
+use crate::vector_3::Vector3;
+
+//A HOMOGENEOUS MEDIUM FILLING A CLOSED boundary, HIT WHERE A RAY SCATTERS INSIDE IT
+pub struct ConstantMedium {
+    pub boundary: Arc<dyn Surface>,
+    pub medium: Medium,
+}
+
+impl Surface for ConstantMedium {
+
+    //WHERE IT SCATTERS IS RANDOM, SO ONLY hit_random CAN FIND IT
+    fn hit(&self, _ray: &Ray, _min: f32, _max: f32) -> Option<Hit> {
+        None
+    }
+
+    //ONE FREE FLIGHT IS SPENT ACROSS EVERY STRETCH OF [min, max] INSIDE THE BOUNDARY IN TURN, THE MEDIUM IS THE SAME IN ALL OF THEM
+    fn hit_random(&self, ray: &Ray, min: f32, max: f32, rng: &mut xorshift::StdRng) -> Option<Hit> {
+        let mut crossings: Vec<Hit> = self.boundary.crossings(ray);
+        crossings.sort_by(|a: &Hit, b: &Hit| a.distance.total_cmp(&b.distance));
+
+        let length: f32 = ray.direction.length();
+        let mut flight: Option<f32> = None;
+        let mut depth: i32 = 0;
+        let mut enter: f32 = f32::NEG_INFINITY;
+        for crossing in crossings {
+            let inside: bool = depth > 0;
+            depth += if crossing.outward {1} else {-1};
+            if !inside && depth > 0 {
+                enter = crossing.distance;
+            } else if inside && depth <= 0 {
+                let (start, end): (f32, f32) = (f32::max(enter, min), f32::min(crossing.distance, max));
+                if start >= end {
+                    continue;
+                }
+                let remaining: &mut f32 = flight.get_or_insert_with(|| self.medium.sample_distance(rng.next_f32()) / length);
+                if *remaining < end - start {
+                    //THE NORMAL AND TANGENT ARE ARBITRARY, PHASE FUNCTIONS IGNORE THEM
+                    return Some(Hit::new(ray, start + *remaining, -ray.direction.unit_vector(), self.medium.phase.clone(), (0.0, 0.0), Vector3::ZERO));
+                }
+                *remaining -= end - start;
+            }
+        }
+        None
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::sphere::Sphere;
+    use crate::hit::HitList;
+    use crate::material::Material;
+    use crate::material::isotropic::Isotropic;
+    use crate::texture::solid_colour::SolidColour;
+
+    const SAMPLES: usize = 40000;
+
+    fn phase() -> Arc<dyn Material> {
+        Arc::new(Isotropic { albedo: Arc::new(SolidColour { colour: Vector3::ONE }) })
+    }
+
+    //THE RAYS BELOW RUN ALONG THE X AXIS, SO THEY CROSS THE BALL FROM from TO to
+    fn ball(from: f32, to: f32) -> Sphere {
+        Sphere { center: V3!(0.5 * (from + to), 0.0, 0.0), radius: 0.5 * (to - from), material: phase() }
+    }
+
+    fn medium(boundary: impl Surface + 'static, density: f32) -> ConstantMedium {
+        ConstantMedium { boundary: Arc::new(boundary), medium: Medium { density, phase: phase() } }
+    }
+
+    //FRACTION OF RAYS FROM origin ALONG +X THAT CROSS WITHOUT SCATTERING
+    fn transmitted(medium: &ConstantMedium, origin: Vector3) -> f32 {
+        let mut rng: xorshift::StdRng = xorshift::SeedableRng::from_seed(&[1usize, 2, 3, 4][..]);
+        let ray: Ray = Ray { origin, direction: V3!(2.0, 0.0, 0.0), time: 0.0, differentials: None };
+        let passed: usize = (0..SAMPLES).filter(|_| medium.hit_random(&ray, 0.0, f32::INFINITY, &mut rng).is_none()).count();
+        passed as f32 / SAMPLES as f32
+    }
+
+    #[test]
+    fn mean_free_path_is_the_inverse_density() {
+        let medium: ConstantMedium = medium(ball(-1000.0, 1000.0), 2.0);
+        let mut rng: xorshift::StdRng = xorshift::SeedableRng::from_seed(&[5usize, 6, 7, 8][..]);
+        //A DIRECTION OF LENGTH 2 CHECKS THE FLIGHT IS MEASURED IN DISTANCE, NOT ALONG THE RAY
+        let ray: Ray = Ray { origin: Vector3::ZERO, direction: V3!(0.0, 0.0, 2.0), time: 0.0, differentials: None };
+        let total: f32 = (0..SAMPLES).map(|_| {
+            let hit: Hit = medium.hit_random(&ray, 0.0, f32::INFINITY, &mut rng).unwrap();
+            (hit.point - ray.origin).length()
+        }).sum();
+        let mean: f32 = total / SAMPLES as f32;
+        assert!((mean - 0.5).abs() < 0.01, "mean free path {}", mean);
+    }
+
+    #[test]
+    fn transmittance_follows_beer_lambert() {
+        let fraction: f32 = transmitted(&medium(ball(0.0, 1.0), 1.5), V3!(-1.0, 0.0, 0.0));
+        assert!((fraction - f32::exp(-1.5)).abs() < 0.01, "transmitted {}", fraction);
+    }
+
+    #[test]
+    fn rays_starting_inside_only_cross_the_rest() {
+        let fraction: f32 = transmitted(&medium(ball(0.0, 1.0), 1.5), V3!(0.6, 0.0, 0.0));
+        assert!((fraction - f32::exp(-0.6)).abs() < 0.01, "transmitted {}", fraction);
+    }
+
+    #[test]
+    fn separate_parts_of_the_boundary_add_up() {
+        let boundary: HitList = HitList { objects: vec![Box::new(ball(0.0, 1.0)), Box::new(ball(2.0, 2.5))] };
+        let fraction: f32 = transmitted(&medium(boundary, 1.0), V3!(-1.0, 0.0, 0.0));
+        assert!((fraction - f32::exp(-1.5)).abs() < 0.01, "transmitted {}", fraction);
+    }
+
+    #[test]
+    fn plain_hits_never_scatter() {
+        let ray: Ray = Ray { origin: V3!(-1.0, 0.0, 0.0), direction: V3!(1.0, 0.0, 0.0), time: 0.0, differentials: None };
+        assert!(medium(ball(0.0, 1.0), 100.0).hit(&ray, 0.0, f32::INFINITY).is_none());
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hit::{Surface, Hit};
+use crate::ray::Ray;
+
+#[derive(Copy, Clone)]
+pub enum Operation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl Operation {
+    fn inside(&self, left: bool, right: bool) -> bool {
+        match self {
+            Operation::Union => left || right,
+            Operation::Intersection => left && right,
+            Operation::Difference => left && !right,
+        }
+    }
+}
+
+//BOOLEAN COMBINATION OF TWO CLOSED SURFACES, EACH PART KEEPS THE MATERIAL AND UVS OF ITS OPERAND
+pub struct Csg {
+    pub operation: Operation,
+    pub left: Arc<dyn Surface>,
+    pub right: Arc<dyn Surface>,
+}
+
+impl Surface for Csg {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        match self.bounding_box() {
+            Some(bounds) if !bounds.hit(ray, min, max) => return None,
+            _ => {}
+        }
+        self.crossings(ray).into_iter()
+            .filter(|hit: &Hit| min < hit.distance && hit.distance < max)
+            .min_by(|a: &Hit, b: &Hit| a.distance.total_cmp(&b.distance))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        match self.operation {
+            Operation::Union => Some(Aabb::union(&self.left.bounding_box()?, &self.right.bounding_box()?)),
+            Operation::Intersection | Operation::Difference => self.left.bounding_box(),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.left.is_closed() && self.right.is_closed()
+    }
+
+    //WALKS BOTH OPERANDS' CROSSINGS IN ORDER, COUNTING HOW DEEP INSIDE EACH THE RAY IS, AND KEEPS THE ONES WHERE THE RESULT CHANGES
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        let mut events: Vec<(bool, Hit)> = self.left.crossings(ray).into_iter().map(|hit: Hit| (true, hit)).collect();
+        events.extend(self.right.crossings(ray).into_iter().map(|hit: Hit| (false, hit)));
+        events.sort_by(|a, b| a.1.distance.total_cmp(&b.1.distance));
+
+        let mut depth: (i32, i32) = (0, 0);
+        let mut crossings: Vec<Hit> = Vec::new();
+        for (left, mut hit) in events {
+            let before: bool = self.operation.inside(depth.0 > 0, depth.1 > 0);
+            let step: i32 = if hit.outward {1} else {-1};
+            if left {depth.0 += step} else {depth.1 += step}
+            let after: bool = self.operation.inside(depth.0 > 0, depth.1 > 0);
+            //NORMALS ALREADY FACE THE RAY, ONLY WHETHER IT ENTERS THE RESULT CAN CHANGE
+            if before != after {
+                hit.outward = after;
+                crossings.push(hit);
+            }
+        }
+        crossings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::quad::Quad;
+    use crate::geometry::sphere::Sphere;
+    use crate::material::Material;
+    use crate::material::lambertian::Lambertian;
+    use crate::texture::solid_colour::SolidColour;
+    use crate::vector_3::Vector3;
+
+    fn grey() -> Arc<dyn Material> {
+        Arc::new(Lambertian { albedo: Arc::new(SolidColour { colour: V3!(0.5, 0.5, 0.5) }) })
+    }
+
+    //UNIT SPHERES AT X = 0 AND X = 1, A RAY ALONG X FROM -5 CROSSES THEM OVER [4, 6] AND [5, 7]
+    fn crossings(operation: Operation) -> Vec<(f32, bool)> {
+        let csg: Csg = Csg {
+            operation,
+            left: Arc::new(Sphere { center: V3!(0.0, 0.0, 0.0), radius: 1.0, material: grey() }),
+            right: Arc::new(Sphere { center: V3!(1.0, 0.0, 0.0), radius: 1.0, material: grey() }),
+        };
+        let ray: Ray = Ray { origin: V3!(-5.0, 0.0, 0.0), direction: V3!(1.0, 0.0, 0.0), time: 0.0, differentials: None };
+        let mut crossings: Vec<(f32, bool)> = csg.crossings(&ray).into_iter().map(|hit: Hit| (hit.distance, hit.outward)).collect();
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        crossings
+    }
+
+    fn assert_intervals(crossings: &[(f32, bool)], expected: &[(f32, bool)]) {
+        assert_eq!(crossings.len(), expected.len(), "{:?}", crossings);
+        for ((distance, outward), (expected_distance, expected_outward)) in crossings.iter().zip(expected) {
+            assert!((distance - expected_distance).abs() < 1e-4, "{:?}", crossings);
+            assert_eq!(outward, expected_outward, "{:?}", crossings);
+        }
+    }
+
+    #[test]
+    fn union_spans_both_spheres() {
+        assert_intervals(&crossings(Operation::Union), &[(4.0, true), (7.0, false)]);
+    }
+
+    #[test]
+    fn intersection_is_the_overlap() {
+        assert_intervals(&crossings(Operation::Intersection), &[(5.0, true), (6.0, false)]);
+    }
+
+    #[test]
+    fn difference_ends_where_the_right_sphere_begins() {
+        assert_intervals(&crossings(Operation::Difference), &[(4.0, true), (5.0, false)]);
+    }
+
+    #[test]
+    fn hit_is_the_first_crossing_in_range() {
+        let csg: Csg = Csg {
+            operation: Operation::Difference,
+            left: Arc::new(Sphere { center: V3!(0.0, 0.0, 0.0), radius: 1.0, material: grey() }),
+            right: Arc::new(Sphere { center: V3!(1.0, 0.0, 0.0), radius: 1.0, material: grey() }),
+        };
+        let ray: Ray = Ray { origin: V3!(-5.0, 0.0, 0.0), direction: V3!(1.0, 0.0, 0.0), time: 0.0, differentials: None };
+        assert!((csg.hit(&ray, 0.001, f32::INFINITY).unwrap().distance - 4.0).abs() < 1e-4);
+        assert!((csg.hit(&ray, 4.5, f32::INFINITY).unwrap().distance - 5.0).abs() < 1e-4);
+        assert!(csg.hit(&ray, 5.5, f32::INFINITY).is_none());
+    }
+
+    #[test]
+    fn open_operands_are_not_closed() {
+        let sphere: Arc<dyn Surface> = Arc::new(Sphere { center: Vector3::ZERO, radius: 1.0, material: grey() });
+        let quad: Arc<dyn Surface> = Arc::new(Quad { corner: Vector3::ZERO, u: V3!(1.0, 0.0, 0.0), v: V3!(0.0, 1.0, 0.0), material: grey() });
+        assert!(Csg { operation: Operation::Union, left: sphere.clone(), right: sphere.clone() }.is_closed());
+        assert!(!Csg { operation: Operation::Union, left: sphere, right: quad }.is_closed());
+    }
+}
\ No newline at end of file
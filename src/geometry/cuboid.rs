@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hit::{Surface, Hit};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+//AXIS ALIGNED BOX, EACH FACE IS MAPPED TO THE FULL UV SQUARE
+pub struct Cuboid {
+    pub min: Point3,
+    pub max: Point3,
+    pub material: Arc<dyn Material>
+}
+
+impl Cuboid {
+    //ENTRY AND EXIT DISTANCES ALONG WITH THE AXES OF THE FACES CROSSED
+    fn slabs(&self, ray: &Ray) -> Option<((f32, usize), (f32, usize))> {
+        let mut near: (f32, usize) = (f32::NEG_INFINITY, 0);
+        let mut far: (f32, usize) = (f32::INFINITY, 0);
+
+        for axis in 0..3 {
+            if ray.direction[axis] == 0.0 {
+                //PARALLEL TO THE SLAB, EITHER ALWAYS OR NEVER INSIDE IT
+                if ray.origin[axis] < self.min[axis] || ray.origin[axis] > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let inverse: f32 = 1.0 / ray.direction[axis];
+            let mut t0: f32 = (self.min[axis] - ray.origin[axis]) * inverse;
+            let mut t1: f32 = (self.max[axis] - ray.origin[axis]) * inverse;
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > near.0 {
+                near = (t0, axis);
+            }
+            if t1 < far.0 {
+                far = (t1, axis);
+            }
+        }
+        if near.0 <= far.0 {Some((near, far))} else {None}
+    }
+
+    //ENTERING THROUGH THE FACE AGAINST THE RAY ON axis, LEAVING THROUGH THE ONE ALONG IT
+    fn at(&self, ray: &Ray, t: f32, axis: usize, entering: bool) -> Hit {
+        let sign: f32 = if (ray.direction[axis] > 0.0) == entering {-1.0} else {1.0};
+        let unit = |axis: usize| match axis {
+            0 => Vector3::X,
+            1 => Vector3::Y,
+            _ => Vector3::Z,
+        };
+
+        let p: Point3 = ray.at(t);
+        let size: Vector3 = self.max - self.min;
+        let (i, j): (usize, usize) = ((axis + 1) % 3, (axis + 2) % 3);
+        let uv: (f32, f32) = ((p[i] - self.min[i]) / size[i], (p[j] - self.min[j]) / size[j]);
+        Hit::new(ray, t, sign * unit(axis), self.material.clone(), uv, unit(i))
+    }
+}
+
+impl Surface for Cuboid {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        match self.slabs(ray) {
+            Some((near, _)) if min < near.0 && near.0 < max => Some(self.at(ray, near.0, near.1, true)),
+            Some((_, far)) if min < far.0 && far.0 < max => Some(self.at(ray, far.0, far.1, false)),
+            _ => None,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::new(self.min, self.max))
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        match self.slabs(ray) {
+            Some((near, far)) => vec![self.at(ray, near.0, near.1, true), self.at(ray, far.0, far.1, false)],
+            None => vec![],
+        }
+    }
+}
\ No newline at end of file
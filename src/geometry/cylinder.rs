@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::geometry::disk::{disk_bounds, intersect_cap};
+use crate::geometry::frame::Frame;
+use crate::geometry::sphere::{solve_quadratic, QuadraticSolution};
+use crate::hit::{Surface, Hit};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+//CAPPED, SIDE UVS ARE THE ANGLE AROUND THE AXIS AND THE FRACTION OF THE HEIGHT, CAP UVS ARE POLAR
+pub struct Cylinder {
+    pub base: Point3,
+    pub top: Point3,
+    pub radius: f32,
+    pub material: Arc<dyn Material>
+}
+
+impl Cylinder {
+    //HITS ON THE SIDE AND BOTH CAPS, AS DISTANCE, LOCAL POINT AND LOCAL NORMAL
+    fn candidates(&self, frame: &Frame, ray: &Ray, min: f32, max: f32) -> Vec<(f32, Vector3, Vector3)> {
+        let height: f32 = (self.top - self.base).length();
+        let origin: Vector3 = frame.to_local(&ray.origin);
+        let direction: Vector3 = frame.to_local_vector(&ray.direction);
+        let mut candidates: Vec<(f32, Vector3, Vector3)> = Vec::with_capacity(4);
+
+        let a: f32 = direction.x * direction.x + direction.y * direction.y;
+        if a > 1e-12 {
+            let b: f32 = 2.0 * (origin.x * direction.x + origin.y * direction.y);
+            let c: f32 = origin.x * origin.x + origin.y * origin.y - self.radius * self.radius;
+            if let QuadraticSolution::Result(one, two) = solve_quadratic(a, b, c) {
+                for t in [one, two] {
+                    let local: Vector3 = origin + t * direction;
+                    if min < t && t < max && (0.0..=height).contains(&local.z) {
+                        candidates.push((t, local, V3!(local.x, local.y, 0.0) / self.radius));
+                    }
+                }
+            }
+        }
+        if let Some((t, local)) = intersect_cap(frame, 0.0, self.radius, ray, min, max) {
+            candidates.push((t, local, -Vector3::W));
+        }
+        if let Some((t, local)) = intersect_cap(frame, height, self.radius, ray, min, max) {
+            candidates.push((t, local, Vector3::W));
+        }
+        candidates
+    }
+
+    fn at(&self, frame: &Frame, ray: &Ray, (t, local, n): (f32, Vector3, Vector3)) -> Hit {
+        let height: f32 = (self.top - self.base).length();
+        let radial: f32 = f32::sqrt(local.x * local.x + local.y * local.y) / self.radius;
+        let uv: (f32, f32) = if n.z == 0.0 {(Frame::azimuth(&local), local.z / height)} else {(Frame::azimuth(&local), radial)};
+        Hit::new(ray, t, frame.to_world_vector(&n), self.material.clone(), uv, frame.azimuthal(&local))
+    }
+}
+
+impl Surface for Cylinder {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let frame: Frame = Frame::new(self.base, &(self.top - self.base));
+        let candidate: (f32, Vector3, Vector3) = self.candidates(&frame, ray, min, max).into_iter().min_by(|a, b| a.0.total_cmp(&b.0))?;
+        Some(self.at(&frame, ray, candidate))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis: Vector3 = self.top - self.base;
+        Some(Aabb::union(&disk_bounds(&self.base, &axis, self.radius), &disk_bounds(&self.top, &axis, self.radius)))
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        let frame: Frame = Frame::new(self.base, &(self.top - self.base));
+        self.candidates(&frame, ray, f32::NEG_INFINITY, f32::INFINITY).into_iter()
+            .map(|candidate| self.at(&frame, ray, candidate))
+            .collect()
+    }
+}
\ No newline at end of file
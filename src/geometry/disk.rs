@@ -0,0 +1,80 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+use xorshift::Rng;
+
+use crate::aabb::Aabb;
+use crate::geometry::frame::Frame;
+use crate::hit::{Surface, Hit, area_pdf, emitted_power};
+use crate::light::tree::LightBounds;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+//UVS ARE THE ANGLE AROUND normal AND THE FRACTION OF THE RADIUS
+pub struct Disk {
+    pub center: Point3,
+    pub normal: Vector3,
+    pub radius: f32,
+    pub material: Arc<dyn Material>
+}
+
+//EXACT BOX OF A CIRCLE, ALSO USED FOR THE CAPS OF CYLINDERS AND CONES
+pub fn disk_bounds(center: &Point3, normal: &Vector3, radius: f32) -> Aabb {
+    let n: Vector3 = normal.unit_vector();
+    let extent = |c: f32| radius * f32::sqrt(f32::max(0.0, 1.0 - c * c)) + 0.0001;
+    let extent: Vector3 = V3!(extent(n.x), extent(n.y), extent(n.z));
+    Aabb::new(*center - extent, *center + extent)
+}
+
+//DISTANCE AND LOCAL POINT OF THE CIRCLE OF radius AT HEIGHT height IN frame
+pub fn intersect_cap(frame: &Frame, height: f32, radius: f32, ray: &Ray, min: f32, max: f32) -> Option<(f32, Vector3)> {
+    let origin: Vector3 = frame.to_local(&ray.origin);
+    let direction: Vector3 = frame.to_local_vector(&ray.direction);
+    if direction.z.abs() < 1e-8 {
+        return None;
+    }
+    let t: f32 = (height - origin.z) / direction.z;
+    if !(min < t && t < max) {
+        return None;
+    }
+    let local: Vector3 = origin + t * direction;
+    if local.x * local.x + local.y * local.y <= radius * radius {Some((t, local))} else {None}
+}
+
+impl Surface for Disk {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let frame: Frame = Frame::new(self.center, &self.normal);
+        let (t, local): (f32, Vector3) = intersect_cap(&frame, 0.0, self.radius, ray, min, max)?;
+        let uv: (f32, f32) = (Frame::azimuth(&local), f32::sqrt(local.x * local.x + local.y * local.y) / self.radius);
+        Some(Hit::new(ray, t, frame.w, self.material.clone(), uv, frame.azimuthal(&local)))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(disk_bounds(&self.center, &self.normal, self.radius))
+    }
+
+    //UNIFORM OVER THE AREA
+    fn sample(&self, origin: &Point3, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        let frame: Frame = Frame::new(self.center, &self.normal);
+        let r: f32 = self.radius * f32::sqrt(rng.next_f32());
+        let phi: f32 = 2.0 * PI * rng.next_f32();
+        Some(self.center + frame.to_world_vector(&V3!(r * f32::cos(phi), r * f32::sin(phi), 0.0)) - *origin)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let frame: Frame = Frame::new(self.center, &self.normal);
+        match intersect_cap(&frame, 0.0, self.radius, &Ray{ origin: *origin, direction: *direction, time: 0.0, differentials: None }, 0.001, f32::INFINITY) {
+            Some((t, _)) => area_pdf(direction, t, &frame.w, PI * self.radius * self.radius),
+            None => 0.0,
+        }
+    }
+
+    fn power(&self) -> f32 {
+        emitted_power(&*self.material, PI * self.radius * self.radius)
+    }
+
+    fn light_bounds(&self) -> Option<LightBounds> {
+        Some(LightBounds::flat(self.bounding_box()?, self.power(), &self.normal))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use crate::vector_3::{Vector3, Point3};
+
+//ORTHONORMAL FRAME WHOSE w IS THE AXIS OF A SHAPE, u AND v SPAN ITS CROSS SECTION
+pub struct Frame {
+    pub origin: Point3,
+    pub u: Vector3,
+    pub v: Vector3,
+    pub w: Vector3,
+}
+
+impl Frame {
+    pub fn new(origin: Point3, axis: &Vector3) -> Frame {
+        let w: Vector3 = axis.unit_vector();
+        let (u, v): (Vector3, Vector3) = Vector3::orthonormal_basis(&w);
+        Frame { origin, u, v, w }
+    }
+
+    pub fn to_local(&self, p: &Point3) -> Vector3 {
+        self.to_local_vector(&(*p - self.origin))
+    }
+
+    pub fn to_local_vector(&self, d: &Vector3) -> Vector3 {
+        V3!(Vector3::dot(d, &self.u), Vector3::dot(d, &self.v), Vector3::dot(d, &self.w))
+    }
+
+    pub fn to_world_vector(&self, d: &Vector3) -> Vector3 {
+        d.x * self.u + d.y * self.v + d.z * self.w
+    }
+
+    //WORLD DIRECTION OF INCREASING azimuth AT A LOCAL POINT
+    pub fn azimuthal(&self, local: &Vector3) -> Vector3 {
+        self.to_world_vector(&V3!(-local.y, local.x, 0.0))
+    }
+
+    //ANGLE AROUND w OF A LOCAL POINT, IN [0, 1)
+    pub fn azimuth(local: &Vector3) -> f32 {
+        let phi: f32 = f32::atan2(local.y, local.x);
+        let phi: f32 = if phi < 0.0 {phi + 2.0 * std::f32::consts::PI} else {phi};
+        phi / (2.0 * std::f32::consts::PI)
+    }
+}
\ No newline at end of file
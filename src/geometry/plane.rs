@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::geometry::frame::Frame;
+use crate::hit::{Surface, Hit};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+//INFINITE, UVS ARE DISTANCES ALONG THE PLANE FROM point
+pub struct Plane {
+    pub point: Point3,
+    pub normal: Vector3,
+    pub material: Arc<dyn Material>
+}
+
+impl Surface for Plane {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let denominator: f32 = Vector3::dot(&self.normal, &ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+        let t: f32 = Vector3::dot(&(self.point - ray.origin), &self.normal) / denominator;
+        if !(min < t && t < max) {
+            return None;
+        }
+
+        let frame: Frame = Frame::new(self.point, &self.normal);
+        let local: Vector3 = frame.to_local(&ray.at(t));
+        Some(Hit::new(ray, t, frame.w, self.material.clone(), (local.x, local.y), frame.u))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
\ No newline at end of file
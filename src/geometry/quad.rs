@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use xorshift::Rng;
+
+use crate::aabb::Aabb;
+use crate::hit::{Surface, Hit, area_pdf, emitted_power};
+use crate::light::tree::LightBounds;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+//PARALLELOGRAM corner + a * u + b * v WITH a AND b IN [0, 1], WHICH ARE ALSO ITS UVS
+pub struct Quad {
+    pub corner: Point3,
+    pub u: Vector3,
+    pub v: Vector3,
+    pub material: Arc<dyn Material>
+}
+
+impl Quad {
+    //DISTANCE AND PLANE COORDINATES
+    fn intersect(&self, ray: &Ray, min: f32, max: f32) -> Option<(f32, f32, f32)> {
+        let n: Vector3 = Vector3::cross(&self.u, &self.v);
+        let denominator: f32 = Vector3::dot(&n, &ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+        let t: f32 = Vector3::dot(&(self.corner - ray.origin), &n) / denominator;
+        if !(min < t && t < max) {
+            return None;
+        }
+
+        let w: Vector3 = n / n.length_squared();
+        let h: Vector3 = ray.at(t) - self.corner;
+        let a: f32 = Vector3::dot(&w, &Vector3::cross(&h, &self.v));
+        let b: f32 = Vector3::dot(&w, &Vector3::cross(&self.u, &h));
+        if (0.0..=1.0).contains(&a) && (0.0..=1.0).contains(&b) {Some((t, a, b))} else {None}
+    }
+}
+
+impl Surface for Quad {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let (t, a, b): (f32, f32, f32) = self.intersect(ray, min, max)?;
+        let n: Vector3 = Vector3::cross(&self.u, &self.v).unit_vector();
+        Some(Hit::new(ray, t, n, self.material.clone(), (a, b), self.u))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        //PAD SO AXIS ALIGNED QUADS STILL HAVE VOLUME
+        let padding: Vector3 = 0.0001 * Vector3::ONE;
+        let diagonal: Aabb = Aabb::new(self.corner, self.corner + self.u + self.v);
+        let other: Aabb = Aabb::new(self.corner + self.u, self.corner + self.v);
+        let bounds: Aabb = Aabb::union(&diagonal, &other);
+        Some(Aabb::new(bounds.min - padding, bounds.max + padding))
+    }
+
+    //UNIFORM OVER THE AREA
+    fn sample(&self, origin: &Point3, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        Some(self.corner + rng.next_f32() * self.u + rng.next_f32() * self.v - *origin)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        match self.intersect(&Ray{ origin: *origin, direction: *direction, time: 0.0, differentials: None }, 0.001, f32::INFINITY) {
+            Some((t, _, _)) => {
+                let n: Vector3 = Vector3::cross(&self.u, &self.v);
+                area_pdf(direction, t, &n.unit_vector(), n.length())
+            }
+            None => 0.0,
+        }
+    }
+
+    fn power(&self) -> f32 {
+        emitted_power(&*self.material, Vector3::cross(&self.u, &self.v).length())
+    }
+
+    fn light_bounds(&self) -> Option<LightBounds> {
+        Some(LightBounds::flat(self.bounding_box()?, self.power(), &Vector3::cross(&self.u, &self.v)))
+    }
+}
\ No newline at end of file
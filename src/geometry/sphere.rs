@@ -1,22 +1,27 @@
 use crate::vector_3::Vector3;
 use crate::vector_3::Point3;
-use std::rc::Rc;
-use crate::hit::{Surface, HitResult};
+use std::sync::Arc;
+use std::f32::consts::PI;
+use crate::hit::{Surface, Hit, area_pdf, emitted_power};
+use crate::ray::Ray;
+use xorshift::Rng;
 use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::light::tree::LightBounds;
 
 
 pub struct Sphere {
     pub center: Point3,
     pub radius: f32,
-    pub material: Rc<dyn Material>
+    pub material: Arc<dyn Material>
 }
 
-enum QuadraticSolution {
+pub enum QuadraticSolution {
     None,
     Result(f32, f32)
 }
 
-fn solve_quadratic(a : f32, b : f32, c : f32) -> QuadraticSolution {
+pub fn solve_quadratic(a : f32, b : f32, c : f32) -> QuadraticSolution {
 
     let discriminant : f32 = b * b - 4.0 * a * c;
     if discriminant < 0.0 {
@@ -27,34 +32,147 @@ fn solve_quadratic(a : f32, b : f32, c : f32) -> QuadraticSolution {
     }
 }
 
+//LONGITUDE AND LATITUDE OF A POINT ON THE UNIT SPHERE, U STARTS AT -X AND V AT THE SOUTH POLE
+fn sphere_uv(n: &Vector3) -> (f32, f32) {
+    let phi: f32 = f32::atan2(-n.z, n.x) + PI;
+    let theta: f32 = f32::acos((-n.y).clamp(-1.0, 1.0));
+    (phi / (2.0 * PI), theta / PI)
+}
+
+fn at(center: &Point3, radius: f32, material: &Arc<dyn Material>, ray : &Ray, t : f32) -> Hit {
+    let n: Vector3 = (ray.at(t) - *center) / radius;
+    //DERIVATIVE OF THE POINT WITH RESPECT TO THE LONGITUDE
+    let tangent: Vector3 = V3!(n.z, 0.0, -n.x);
+    Hit::new(ray, t, n, material.clone(), sphere_uv(&n), tangent)
+}
+
+fn roots(center: &Point3, radius: f32, ray : &Ray) -> QuadraticSolution {
+    let o : Vector3 = ray.origin - *center;
+    let a : f32 = ray.direction.length_squared();
+    let b : f32 = 2.0 * Vector3::dot(&o, &ray.direction);
+    let c : f32 = o.length_squared() - radius * radius;
+    solve_quadratic(a, b, c)
+}
+
+fn hit(center: &Point3, radius: f32, material: &Arc<dyn Material>, ray : &Ray, min : f32, max : f32) -> Option<Hit> {
+
+    match roots(center, radius, ray) {
+        QuadraticSolution::None => None,
+        QuadraticSolution::Result(one, two) => {
+            if min < one && one < max {
+                Some(at(center, radius, material, ray, one))
+            } else if min < two && two < max {
+                Some(at(center, radius, material, ray, two))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn crossings(center: &Point3, radius: f32, material: &Arc<dyn Material>, ray : &Ray) -> Vec<Hit> {
+    match roots(center, radius, ray) {
+        QuadraticSolution::None => vec![],
+        QuadraticSolution::Result(one, two) => vec![at(center, radius, material, ray, one), at(center, radius, material, ray, two)],
+    }
+}
+
 impl Surface for Sphere {
 
-    fn hit(&self, ray : &crate::ray::Ray, min : f32, max : f32) -> HitResult {
-
-        let o : Vector3 = ray.origin - self.center;
-        let a : f32 = ray.direction.length_squared();
-        let b : f32 = 2.0 * Vector3::dot(&o, &ray.direction);
-        let c : f32 = o.length_squared() - self.radius * self.radius;
-        
-        match solve_quadratic(a, b, c) {
-            QuadraticSolution::None => HitResult::None,
-            QuadraticSolution::Result(one, two) => {
-                if min < one && one < max {
-                    let p: Point3 = ray.at(one);
-                    let n: Vector3 = (p - self.center) / self.radius;
-                    let outward: bool = Vector3::dot(&ray.direction, &n) < 0.0;
-                    let n: Vector3 = if outward {n} else {-n};
-                    HitResult::Hit(p, n, one, self.material.clone(), outward)
-                } else if min < two && two < max {
-                    let p: Point3 = ray.at(two);
-                    let n: Vector3 = (p - self.center) / self.radius;
-                    let outward: bool = Vector3::dot(&ray.direction, &n) < 0.0;
-                    let n: Vector3 = if outward {n} else {-n};
-                    HitResult::Hit(p, n, two, self.material.clone(), outward)
+    fn hit(&self, ray : &Ray, min : f32, max : f32) -> Option<Hit> {
+        hit(&self.center, self.radius, &self.material, ray, min, max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent: Vector3 = self.radius.abs() * Vector3::ONE;
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+
+    //UNIFORM OVER THE CONE OF DIRECTIONS THE SPHERE SUBTENDS, OR OVER ITS AREA FROM INSIDE
+    fn sample(&self, origin: &Point3, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        let radius: f32 = self.radius.abs();
+        let direction: Vector3 = self.center - *origin;
+        let distance_squared: f32 = direction.length_squared();
+        if distance_squared <= radius * radius {
+            return Some(self.center + radius * Vector3::random_unit_vector(rng) - *origin);
+        }
+
+        let cos_max: f32 = f32::sqrt(1.0 - radius * radius / distance_squared);
+        let z: f32 = 1.0 + rng.next_f32() * (cos_max - 1.0);
+        let phi: f32 = 2.0 * PI * rng.next_f32();
+        let sin: f32 = f32::sqrt(f32::max(0.0, 1.0 - z * z));
+
+        let w: Vector3 = direction.unit_vector();
+        let (u, v): (Vector3, Vector3) = Vector3::orthonormal_basis(&w);
+        Some(sin * f32::cos(phi) * u + sin * f32::sin(phi) * v + z * w)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        match self.hit(&Ray{ origin: *origin, direction: *direction, time: 0.0, differentials: None }, 0.001, f32::INFINITY) {
+            Some(hit) => {
+                let radius_squared: f32 = self.radius * self.radius;
+                let distance_squared: f32 = (self.center - *origin).length_squared();
+                if distance_squared > radius_squared {
+                    //1 - cos_max WITHOUT CANCELLATION FOR SMALL, DISTANT SPHERES
+                    let cos_max: f32 = f32::sqrt(1.0 - radius_squared / distance_squared);
+                    let solid_angle: f32 = 2.0 * PI * (radius_squared / distance_squared) / (1.0 + cos_max);
+                    1.0 / solid_angle
                 } else {
-                    HitResult::None
+                    area_pdf(direction, hit.distance, &hit.normal, 4.0 * PI * radius_squared)
                 }
             }
+            None => 0.0,
         }
     }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn power(&self) -> f32 {
+        emitted_power(&*self.material, 4.0 * PI * self.radius * self.radius)
+    }
+
+    fn light_bounds(&self) -> Option<LightBounds> {
+        Some(LightBounds::omnidirectional(self.bounding_box()?, self.power()))
+    }
+
+    fn crossings(&self, ray : &Ray) -> Vec<Hit> {
+        crossings(&self.center, self.radius, &self.material, ray)
+    }
+}
+
+//CENTER MOVES LINEARLY FROM center[0] AT TIME 0 TO center[1] AT TIME 1, HOLDING THEM OUTSIDE
+pub struct MovingSphere {
+    pub center: [Point3; 2],
+    pub radius: f32,
+    pub material: Arc<dyn Material>
+}
+
+impl MovingSphere {
+    fn center(&self, time: f32) -> Point3 {
+        self.center[0] + time.clamp(0.0, 1.0) * (self.center[1] - self.center[0])
+    }
+}
+
+impl Surface for MovingSphere {
+
+    fn hit(&self, ray : &Ray, min : f32, max : f32) -> Option<Hit> {
+        hit(&self.center(ray.time), self.radius, &self.material, ray, min, max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent: Vector3 = self.radius.abs() * Vector3::ONE;
+        let start: Aabb = Aabb::new(self.center[0] - extent, self.center[0] + extent);
+        let end: Aabb = Aabb::new(self.center[1] - extent, self.center[1] + extent);
+        Some(Aabb::union(&start, &end))
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn crossings(&self, ray : &Ray) -> Vec<Hit> {
+        crossings(&self.center(ray.time), self.radius, &self.material, ray)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,159 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::geometry::frame::Frame;
+use crate::hit::{Surface, Hit};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+//RING AROUND normal, UVS ARE THE ANGLE AROUND THE RING AND THE ANGLE AROUND THE TUBE
+pub struct Torus {
+    pub center: Point3,
+    pub normal: Vector3,
+    pub major_radius: f32,
+    pub minor_radius: f32,
+    pub material: Arc<dyn Material>
+}
+
+//LARGEST REAL ROOT OF x^3 + a x^2 + b x + c
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    let q: f64 = (a * a - 3.0 * b) / 9.0;
+    let r: f64 = (2.0 * a * a * a - 9.0 * a * b + 27.0 * c) / 54.0;
+    let root: f64 = if r * r < q * q * q {
+        let theta: f64 = f64::acos((r / f64::sqrt(q * q * q)).clamp(-1.0, 1.0));
+        let s: f64 = -2.0 * f64::sqrt(q);
+        [0.0, 2.0, -2.0].iter()
+            .map(|k: &f64| s * f64::cos((theta + k * PI) / 3.0) - a / 3.0)
+            .fold(f64::NEG_INFINITY, f64::max)
+    } else {
+        let big: f64 = -r.signum() * f64::cbrt(r.abs() + f64::sqrt(r * r - q * q * q));
+        let small: f64 = if big == 0.0 {0.0} else {q / big};
+        big + small - a / 3.0
+    };
+    //POLISH
+    (0..2).fold(root, |x: f64, _| {
+        let derivative: f64 = (3.0 * x + 2.0 * a) * x + b;
+        if derivative == 0.0 {x} else {x - (((x + a) * x + b) * x + c) / derivative}
+    })
+}
+
+fn real_quadratic_roots(b: f64, c: f64, roots: &mut Vec<f64>) {
+    let discriminant: f64 = b * b - 4.0 * c;
+    if discriminant >= 0.0 {
+        roots.push(0.5 * (-b - discriminant.sqrt()));
+        roots.push(0.5 * (-b + discriminant.sqrt()));
+    }
+}
+
+//FERRARI, REAL ROOTS OF x^4 + a x^3 + b x^2 + c x + d
+fn solve_quartic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    //DEPRESSED y^4 + p y^2 + q y + r WITH x = y - a / 4
+    let p: f64 = b - 3.0 * a * a / 8.0;
+    let q: f64 = c - a * b / 2.0 + a * a * a / 8.0;
+    let r: f64 = d - a * c / 4.0 + a * a * b / 16.0 - 3.0 * a * a * a * a / 256.0;
+
+    let mut roots: Vec<f64> = Vec::with_capacity(4);
+    if q.abs() < 1e-12 {
+        //BIQUADRATIC
+        let mut squares: Vec<f64> = Vec::with_capacity(2);
+        real_quadratic_roots(p, r, &mut squares);
+        for z in squares.into_iter().filter(|z: &f64| *z >= 0.0) {
+            roots.push(z.sqrt());
+            roots.push(-z.sqrt());
+        }
+    } else {
+        //RESOLVENT 8m^3 + 8p m^2 + (2p^2 - 8r) m - q^2 HAS A POSITIVE ROOT
+        let m: f64 = largest_cubic_root(p, p * p / 4.0 - r, -q * q / 8.0);
+        if m <= 0.0 {
+            return roots;
+        }
+        let s: f64 = f64::sqrt(2.0 * m);
+        real_quadratic_roots(-s, p / 2.0 + m + q / (2.0 * s), &mut roots);
+        real_quadratic_roots(s, p / 2.0 + m - q / (2.0 * s), &mut roots);
+    }
+
+    roots.into_iter().map(|y: f64| {
+        //POLISH AGAINST THE ORIGINAL QUARTIC
+        (0..2).fold(y - a / 4.0, |x: f64, _| {
+            let value: f64 = (((x + a) * x + b) * x + c) * x + d;
+            let derivative: f64 = ((4.0 * x + 3.0 * a) * x + 2.0 * b) * x + c;
+            if derivative == 0.0 {x} else {x - value / derivative}
+        })
+    }).collect()
+}
+
+impl Torus {
+    //DISTANCES TO EVERY CROSSING OF THE RAY'S LINE
+    fn roots(&self, frame: &Frame, ray: &Ray) -> Vec<f32> {
+        let origin: Vector3 = frame.to_local(&ray.origin);
+        let direction: Vector3 = frame.to_local_vector(&ray.direction);
+        let length: f64 = direction.length() as f64;
+        if length == 0.0 {
+            return vec![];
+        }
+
+        //UNIT DIRECTION AND ORIGIN MOVED TO THE POINT CLOSEST TO THE CENTER KEEP THE QUARTIC WELL CONDITIONED
+        let [dx, dy, dz] = [direction.x as f64 / length, direction.y as f64 / length, direction.z as f64 / length];
+        let shift: f64 = -(origin.x as f64 * dx + origin.y as f64 * dy + origin.z as f64 * dz);
+        let [ox, oy, oz] = [origin.x as f64 + shift * dx, origin.y as f64 + shift * dy, origin.z as f64 + shift * dz];
+
+        let major: f64 = self.major_radius as f64;
+        let minor: f64 = self.minor_radius as f64;
+        let bound: f64 = major + minor;
+        if ox * ox + oy * oy + oz * oz > bound * bound {
+            return vec![];
+        }
+
+        //(|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + y^2)
+        let e: f64 = ox * ox + oy * oy + oz * oz + major * major - minor * minor;
+        let f: f64 = ox * dx + oy * dy + oz * dz;
+        let four_r2: f64 = 4.0 * major * major;
+        solve_quartic(
+            4.0 * f,
+            4.0 * f * f + 2.0 * e - four_r2 * (dx * dx + dy * dy),
+            4.0 * e * f - 2.0 * four_r2 * (ox * dx + oy * dy),
+            e * e - four_r2 * (ox * ox + oy * oy))
+            .into_iter()
+            .map(|s: f64| ((shift + s) / length) as f32)
+            .collect()
+    }
+
+    fn at(&self, frame: &Frame, ray: &Ray, t: f32) -> Hit {
+        let local: Vector3 = frame.to_local(&ray.at(t));
+        let radial: f32 = f32::sqrt(local.x * local.x + local.y * local.y);
+        let ring: Vector3 = if radial > 0.0 {self.major_radius / radial * V3!(local.x, local.y, 0.0)} else {Vector3::ZERO};
+        let n: Vector3 = (local - ring).unit_vector();
+        let tube: f32 = f32::atan2(local.z, radial - self.major_radius);
+        let tube: f32 = if tube < 0.0 {tube + 2.0 * std::f32::consts::PI} else {tube};
+        let uv: (f32, f32) = (Frame::azimuth(&local), tube / (2.0 * std::f32::consts::PI));
+
+        Hit::new(ray, t, frame.to_world_vector(&n), self.material.clone(), uv, frame.azimuthal(&local))
+    }
+}
+
+impl Surface for Torus {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let frame: Frame = Frame::new(self.center, &self.normal);
+        let t: f32 = self.roots(&frame, ray).into_iter().filter(|t: &f32| min < *t && *t < max).reduce(f32::min)?;
+        Some(self.at(&frame, ray, t))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let w: Vector3 = self.normal.unit_vector();
+        let extent = |c: f32| self.major_radius * f32::sqrt(f32::max(0.0, 1.0 - c * c)) + self.minor_radius;
+        let extent: Vector3 = V3!(extent(w.x), extent(w.y), extent(w.z));
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        let frame: Frame = Frame::new(self.center, &self.normal);
+        self.roots(&frame, ray).into_iter().map(|t: f32| self.at(&frame, ray, t)).collect()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hit::{Surface, Hit};
+use crate::light::tree::LightBounds;
+use crate::ray::{Ray, Differentials};
+use crate::transform::{Transform, Motion};
+use crate::vector_3::{Vector3, Point3};
+
+//THE OBJECT SPACE DIRECTION IS NOT NORMALISED SO DISTANCES STAY THE SAME IN BOTH SPACES
+fn local(transform: &Transform, ray: &Ray) -> Ray {
+    let differentials: Option<Differentials> = ray.differentials.map(|differentials: Differentials| Differentials {
+        origin: differentials.origin.map(|origin: Point3| transform.inverse_point(&origin)),
+        direction: differentials.direction.map(|direction: Vector3| transform.inverse_vector(&direction)),
+    });
+    Ray{ origin: transform.inverse_point(&ray.origin), direction: transform.inverse_vector(&ray.direction), time: ray.time, differentials }
+}
+
+//NESTED INSTANCES ARE LEFT FROM THE INSIDE OUT, SO THE OUTERMOST ONE SETS instance LAST
+fn world(transform: &Transform, instance: usize, hit: Hit) -> Hit {
+    let geometric_normal: Vector3 = transform.normal(&hit.geometric_normal).unit_vector();
+    let mut world: Hit = Hit {
+        point: transform.point(&hit.point),
+        normal: geometric_normal,
+        geometric_normal,
+        tangent: transform.vector(&hit.tangent),
+        instance: Some(instance),
+        ..hit
+    };
+    world.set_shading_normal(transform.normal(&hit.normal));
+    world
+}
+
+fn hit(surface: &dyn Surface, transform: &Transform, instance: usize, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+    surface.hit(&local(transform, ray), min, max).map(|hit: Hit| world(transform, instance, hit))
+}
+
+fn hit_random(surface: &dyn Surface, transform: &Transform, instance: usize, ray: &Ray, min: f32, max: f32, rng: &mut xorshift::StdRng) -> Option<Hit> {
+    surface.hit_random(&local(transform, ray), min, max, rng).map(|hit: Hit| world(transform, instance, hit))
+}
+
+fn crossings(surface: &dyn Surface, transform: &Transform, instance: usize, ray: &Ray) -> Vec<Hit> {
+    surface.crossings(&local(transform, ray)).into_iter().map(|hit: Hit| world(transform, instance, hit)).collect()
+}
+
+//AN INSTANCE OF A SHARED SURFACE, PLACED BY transform
+pub struct Transformed {
+    pub surface: Arc<dyn Surface>,
+    pub transform: Transform,
+    pub instance: usize,
+}
+
+impl Surface for Transformed {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        hit(&*self.surface, &self.transform, self.instance, ray, min, max)
+    }
+
+    fn hit_random(&self, ray: &Ray, min: f32, max: f32, rng: &mut xorshift::StdRng) -> Option<Hit> {
+        hit_random(&*self.surface, &self.transform, self.instance, ray, min, max, rng)
+    }
+
+    //INFINITE CORNERS TIMES ZERO ENTRIES OF THE MATRIX WOULD GIVE NAN, SO AN UNBOUNDED INSIDE STAYS UNBOUNDED
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.transform.bounds(&self.surface.bounding_box().filter(Aabb::is_finite)?))
+    }
+
+    fn sample(&self, origin: &Point3, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        let direction: Vector3 = self.surface.sample(&self.transform.inverse_point(origin), rng)?;
+        Some(self.transform.vector(&direction))
+    }
+
+    //CHANGE OF SOLID ANGLE MEASURE, |det M| / |M w|^3 FOR THE LINEAR MAP M INTO OBJECT SPACE
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let local: Vector3 = self.transform.inverse_vector(&direction.unit_vector());
+        let pdf: f32 = self.surface.pdf_value(&self.transform.inverse_point(origin), &local);
+        let length: f32 = local.length();
+        pdf * self.transform.inverse.determinant_3().abs() / (length * length * length)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.surface.is_closed()
+    }
+
+    //EXACT FOR UNIFORM SCALES, A GUIDE FOR PICKING LIGHTS OTHERWISE
+    fn power(&self) -> f32 {
+        self.surface.power() * self.transform.matrix.determinant_3().abs().powf(2.0 / 3.0)
+    }
+
+    fn light_bounds(&self) -> Option<LightBounds> {
+        let inner: LightBounds = self.surface.light_bounds()?;
+        Some(LightBounds {
+            bounds: self.transform.bounds(&inner.bounds),
+            power: self.power(),
+            axis: self.transform.normal(&inner.axis).unit_vector(),
+            ..inner
+        })
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        crossings(&*self.surface, &self.transform, self.instance, ray)
+    }
+}
+
+//AN INSTANCE WHOSE TRANSFORM DEPENDS ON THE TIME OF THE RAY
+pub struct Animated {
+    pub surface: Arc<dyn Surface>,
+    pub motion: Motion,
+    pub instance: usize,
+}
+
+//TIMES AT WHICH THE BOUNDS ARE TAKEN, EVERY OTHER TIME IS WITHIN HALF A STEP OF ONE OF THEM
+const MOTION_SAMPLES: usize = 64;
+
+impl Surface for Animated {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        hit(&*self.surface, &self.motion.at(ray.time), self.instance, ray, min, max)
+    }
+
+    fn hit_random(&self, ray: &Ray, min: f32, max: f32, rng: &mut xorshift::StdRng) -> Option<Hit> {
+        hit_random(&*self.surface, &self.motion.at(ray.time), self.instance, ray, min, max, rng)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bounds: Aabb = self.surface.bounding_box().filter(Aabb::is_finite)?;
+        let swept: Aabb = (0..=MOTION_SAMPLES)
+            .map(|sample: usize| self.motion.at(sample as f32 / MOTION_SAMPLES as f32).bounds(&bounds))
+            .reduce(|a: Aabb, b: Aabb| Aabb::union(&a, &b))?;
+        //NO POINT GETS FURTHER FROM WHERE IT WAS AT THE NEAREST SAMPLE THAN ITS SPEED TIMES HALF A STEP, HOWEVER FAST IT TURNS
+        let padding: Vector3 = self.motion.speed(&bounds) / (2 * MOTION_SAMPLES) as f32 * Vector3::ONE;
+        Some(Aabb::new(swept.min - padding, swept.max + padding))
+    }
+
+    fn is_closed(&self) -> bool {
+        self.surface.is_closed()
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        crossings(&*self.surface, &self.motion.at(ray.time), self.instance, ray)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bvh::BvhNode;
+    use crate::geometry::plane::Plane;
+    use crate::geometry::sphere::Sphere;
+    use crate::hit::HitList;
+    use crate::material::Material;
+    use crate::material::lambertian::Lambertian;
+    use crate::texture::solid_colour::SolidColour;
+    use crate::transform::Step;
+
+    fn grey() -> Arc<dyn Material> {
+        Arc::new(Lambertian { albedo: Arc::new(SolidColour { colour: V3!(0.5, 0.5, 0.5) }) })
+    }
+
+    fn floor() -> Plane {
+        Plane { point: Vector3::ZERO, normal: V3!(0.0, 1.0, 0.0), material: grey() }
+    }
+
+    //A FLOOR THAT OWNS UP TO BEING INFINITE
+    struct Infinite(Plane);
+
+    impl Surface for Infinite {
+        fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+            self.0.hit(ray, min, max)
+        }
+
+        fn bounding_box(&self) -> Option<Aabb> {
+            Some(Aabb::INFINITE)
+        }
+    }
+
+    #[test]
+    fn infinite_insides_leave_instances_unbounded() {
+        let surface: Arc<dyn Surface> = Arc::new(Infinite(floor()));
+        let transform: Transform = Transform::rotation(&V3!(0.0, 1.0, 0.0), 30.0);
+        assert!(Transformed { surface: surface.clone(), transform, instance: 0 }.bounding_box().is_none());
+        let start: [Step; 1] = [Step::Rotate(V3!(0.0, 1.0, 0.0), 0.0)];
+        let end: [Step; 1] = [Step::Rotate(V3!(0.0, 1.0, 0.0), 90.0)];
+        let motion: Motion = Motion::new(&start, &end).unwrap();
+        assert!(Animated { surface, motion, instance: 0 }.bounding_box().is_none());
+    }
+
+    #[test]
+    fn instanced_planes_are_not_culled_by_the_world() {
+        let prototype: Arc<dyn Surface> = Arc::new(BvhNode::new(HitList { objects: vec![Box::new(floor())] }));
+        let instance: Transformed = Transformed { surface: prototype, transform: Transform::translation(&V3!(0.0, -1.0, 0.0)), instance: 0 };
+        let mut objects: Vec<Box<dyn Surface>> = vec![Box::new(instance)];
+        for index in 0..10 {
+            objects.push(Box::new(Sphere { center: V3!(index as f32, 2.0, 0.0), radius: 0.25, material: grey() }));
+        }
+        let world: BvhNode = BvhNode::new(HitList { objects });
+        let ray: Ray = Ray { origin: V3!(50.0, 1.0, 50.0), direction: V3!(0.0, -1.0, 0.0), time: 0.0, differentials: None };
+        let hit: Hit = world.hit(&ray, 0.001, f32::INFINITY).expect("the instanced plane was culled");
+        assert!((hit.distance - 2.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::vector_3::{Vector3, Point3};
+use crate::ray::Ray;
+use crate::hit::{Surface, Hit, area_pdf, emitted_power};
+use xorshift::Rng;
+use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::light::tree::LightBounds;
+
+pub struct Triangle {
+    pub vertices: [Point3; 3],
+    pub material: Arc<dyn Material>
+}
+
+//NORMALS AND UVS ARE EITHER EMPTY OR ONE PER POSITION
+pub struct TriangleMesh {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vector3>,
+    pub uvs: Vec<(f32, f32)>,
+    pub indices: Vec<[usize; 3]>,
+    pub materials: Vec<Arc<dyn Material>>,
+    //EVERY EDGE IS SHARED BY EXACTLY TWO TRIANGLES
+    pub closed: bool,
+}
+
+pub struct MeshTriangle {
+    pub mesh: Arc<TriangleMesh>,
+    pub index: usize,
+}
+
+//MOLLER-TRUMBORE, RETURNS DISTANCE AND BARYCENTRIC COORDINATES OF THE SECOND AND THIRD VERTICES
+fn intersect(ray: &Ray, vertices: [&Point3; 3], min: f32, max: f32) -> Option<(f32, f32, f32)> {
+    let edge_1: Vector3 = *vertices[1] - *vertices[0];
+    let edge_2: Vector3 = *vertices[2] - *vertices[0];
+
+    let p: Vector3 = Vector3::cross(&ray.direction, &edge_2);
+    let determinant: f32 = Vector3::dot(&edge_1, &p);
+    //RELATIVE TO THE LENGTHS IT IS BUILT FROM, SO TINY AND HUGE TRIANGLES AND RAYS ARE TREATED ALIKE, ZERO FOR PARALLEL RAYS
+    if determinant.abs() <= f32::EPSILON * ray.direction.length() * edge_1.length() * edge_2.length() {
+        return None;
+    }
+    let inverse: f32 = 1.0 / determinant;
+
+    let s: Vector3 = ray.origin - *vertices[0];
+    let b1: f32 = Vector3::dot(&s, &p) * inverse;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let q: Vector3 = Vector3::cross(&s, &edge_1);
+    let b2: f32 = Vector3::dot(&ray.direction, &q) * inverse;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let distance: f32 = Vector3::dot(&edge_2, &q) * inverse;
+    if min < distance && distance < max {Some((distance, b1, b2))} else {None}
+}
+
+fn bounds(vertices: [&Point3; 3]) -> Aabb {
+    //PAD FLAT BOXES SO AXIS ALIGNED TRIANGLES STILL HAVE VOLUME
+    let padding: Vector3 = 0.0001 * Vector3::ONE;
+    let bounds: Aabb = Aabb::union(&Aabb::new(*vertices[0], *vertices[1]), &Aabb::new(*vertices[2], *vertices[2]));
+    Aabb::new(bounds.min - padding, bounds.max + padding)
+}
+
+//UNIFORM OVER THE AREA
+fn sample(vertices: [&Point3; 3], origin: &Point3, rng: &mut xorshift::StdRng) -> Vector3 {
+    let s: f32 = f32::sqrt(rng.next_f32());
+    let b2: f32 = s * rng.next_f32();
+    let b1: f32 = s - b2;
+    (1.0 - s) * *vertices[0] + b1 * *vertices[1] + b2 * *vertices[2] - *origin
+}
+
+fn pdf_value(vertices: [&Point3; 3], origin: &Point3, direction: &Vector3) -> f32 {
+    let ray: Ray = Ray{ origin: *origin, direction: *direction, time: 0.0, differentials: None };
+    match intersect(&ray, vertices, 0.001, f32::INFINITY) {
+        Some((distance, _, _)) => {
+            let cross: Vector3 = Vector3::cross(&(*vertices[1] - *vertices[0]), &(*vertices[2] - *vertices[0]));
+            area_pdf(direction, distance, &cross.unit_vector(), 0.5 * cross.length())
+        }
+        None => 0.0,
+    }
+}
+
+fn area(vertices: [&Point3; 3]) -> f32 {
+    0.5 * Vector3::cross(&(*vertices[1] - *vertices[0]), &(*vertices[2] - *vertices[0])).length()
+}
+
+fn interpolate(values: [Vector3; 3], b1: f32, b2: f32) -> Vector3 {
+    let [v0, v1, v2] = values;
+    (1.0 - b1 - b2) * v0 + b1 * v1 + b2 * v2
+}
+
+impl Surface for Triangle {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let [v0, v1, v2] = &self.vertices;
+        let (distance, b1, b2): (f32, f32, f32) = intersect(ray, [v0, v1, v2], min, max)?;
+        let n: Vector3 = Vector3::cross(&(*v1 - *v0), &(*v2 - *v0)).unit_vector();
+        //U IS THE BARYCENTRIC COORDINATE OF THE SECOND VERTEX
+        Some(Hit::new(ray, distance, n, self.material.clone(), (b1, b2), *v1 - *v0))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let [v0, v1, v2] = &self.vertices;
+        Some(bounds([v0, v1, v2]))
+    }
+
+    fn sample(&self, origin: &Point3, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        let [v0, v1, v2] = &self.vertices;
+        Some(sample([v0, v1, v2], origin, rng))
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        let [v0, v1, v2] = &self.vertices;
+        pdf_value([v0, v1, v2], origin, direction)
+    }
+
+    fn power(&self) -> f32 {
+        let [v0, v1, v2] = &self.vertices;
+        emitted_power(&*self.material, area([v0, v1, v2]))
+    }
+
+    fn light_bounds(&self) -> Option<LightBounds> {
+        let [v0, v1, v2] = &self.vertices;
+        Some(LightBounds::flat(bounds([v0, v1, v2]), self.power(), &Vector3::cross(&(*v1 - *v0), &(*v2 - *v0))))
+    }
+
+    //A SINGLE TRIANGLE ENCLOSES NOTHING, ONLY A CLOSED MESH OF THEM DOES
+    fn crossings(&self, _ray: &Ray) -> Vec<Hit> {
+        vec![]
+    }
+}
+
+impl TriangleMesh {
+
+    pub fn new(positions: Vec<Point3>, normals: Vec<Vector3>, uvs: Vec<(f32, f32)>, indices: Vec<[usize; 3]>, materials: Vec<Arc<dyn Material>>) -> TriangleMesh {
+        //EDGES ARE MATCHED BY POSITION, VERTICES ARE SPLIT WHERE THEIR UVS OR NORMALS DIFFER
+        let key = |index: usize| -> [u32; 3] {
+            let p: &Point3 = &positions[index];
+            [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()]
+        };
+        let mut edges: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+        for [i0, i1, i2] in indices.iter() {
+            for (a, b) in [(*i0, *i1), (*i1, *i2), (*i2, *i0)] {
+                let (a, b): ([u32; 3], [u32; 3]) = (key(a), key(b));
+                *edges.entry(if a < b {(a, b)} else {(b, a)}).or_insert(0) += 1;
+            }
+        }
+        let closed: bool = !edges.is_empty() && edges.values().all(|count: &usize| *count == 2);
+        TriangleMesh { positions, normals, uvs, indices, materials, closed }
+    }
+
+    pub fn triangles(mesh: &Arc<TriangleMesh>) -> impl Iterator<Item = MeshTriangle> + '_ {
+        (0..mesh.indices.len()).map(|index: usize| MeshTriangle { mesh: mesh.clone(), index })
+    }
+
+    fn positions(&self, index: usize) -> [&Point3; 3] {
+        let [i0, i1, i2] = self.indices[index];
+        [&self.positions[i0], &self.positions[i1], &self.positions[i2]]
+    }
+}
+
+impl Surface for MeshTriangle {
+
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let mesh: &TriangleMesh = &self.mesh;
+        let [v0, v1, v2] = mesh.positions(self.index);
+        let (distance, b1, b2): (f32, f32, f32) = intersect(ray, [v0, v1, v2], min, max)?;
+        let [i0, i1, i2] = mesh.indices[self.index];
+        let (edge_1, edge_2): (Vector3, Vector3) = (*v1 - *v0, *v2 - *v0);
+
+        //BARYCENTRIC COORDINATES STAND IN FOR MISSING UVS
+        let (uv, tangent): ((f32, f32), Vector3) = if mesh.uvs.is_empty() {
+            ((b1, b2), edge_1)
+        } else {
+            let [uv0, uv1, uv2] = [mesh.uvs[i0], mesh.uvs[i1], mesh.uvs[i2]];
+            let uv: Vector3 = interpolate([V3!(uv0.0, uv0.1, 0.0), V3!(uv1.0, uv1.1, 0.0), V3!(uv2.0, uv2.1, 0.0)], b1, b2);
+
+            //dP/dU FROM THE UV EDGES, DEGENERATE MAPPINGS FALL BACK TO THE FIRST EDGE
+            let (du_1, dv_1, du_2, dv_2): (f32, f32, f32, f32) = (uv1.0 - uv0.0, uv1.1 - uv0.1, uv2.0 - uv0.0, uv2.1 - uv0.1);
+            let determinant: f32 = du_1 * dv_2 - du_2 * dv_1;
+            let tangent: Vector3 = if determinant.abs() < 1e-12 {edge_1} else {(dv_2 * edge_1 - dv_1 * edge_2) / determinant};
+            ((uv.x, uv.y), tangent)
+        };
+
+        let mut hit: Hit = Hit::new(ray, distance, Vector3::cross(&edge_1, &edge_2).unit_vector(), mesh.materials[self.index].clone(), uv, tangent);
+        hit.primitive = self.index;
+
+        //SMOOTH SHADING
+        if !mesh.normals.is_empty() {
+            hit.set_shading_normal(interpolate([mesh.normals[i0], mesh.normals[i1], mesh.normals[i2]], b1, b2));
+        }
+        Some(hit)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(bounds(self.mesh.positions(self.index)))
+    }
+
+    fn sample(&self, origin: &Point3, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        Some(sample(self.mesh.positions(self.index), origin, rng))
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        pdf_value(self.mesh.positions(self.index), origin, direction)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.mesh.closed
+    }
+
+    fn power(&self) -> f32 {
+        emitted_power(&*self.mesh.materials[self.index], area(self.mesh.positions(self.index)))
+    }
+
+    fn light_bounds(&self) -> Option<LightBounds> {
+        let [v0, v1, v2] = self.mesh.positions(self.index);
+        Some(LightBounds::flat(bounds([v0, v1, v2]), self.power(), &Vector3::cross(&(*v1 - *v0), &(*v2 - *v0))))
+    }
+
+    //EACH TRIANGLE OF A CLOSED MESH GIVES ITS SHARE OF THE MESH'S CROSSINGS
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        if !self.mesh.closed {
+            return vec![];
+        }
+        self.hit(ray, f32::NEG_INFINITY, f32::INFINITY).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::lambertian::Lambertian;
+    use crate::texture::solid_colour::SolidColour;
+
+    fn grey() -> Arc<dyn Material> {
+        Arc::new(Lambertian { albedo: Arc::new(SolidColour { colour: V3!(0.5, 0.5, 0.5) }) })
+    }
+
+    fn tetrahedron(indices: Vec<[usize; 3]>) -> Arc<TriangleMesh> {
+        let positions: Vec<Point3> = vec![V3!(0.0, 0.0, 0.0), V3!(1.0, 0.0, 0.0), V3!(0.0, 1.0, 0.0), V3!(0.0, 0.0, 1.0)];
+        let materials: Vec<Arc<dyn Material>> = vec![grey(); indices.len()];
+        Arc::new(TriangleMesh::new(positions, vec![], vec![], indices, materials))
+    }
+
+    //THROUGH THE MIDDLE OF THE TETRAHEDRON, CROSSING TWO OF ITS FACES
+    const RAY: Ray = Ray { origin: V3!(0.2, 0.2, -1.0), direction: V3!(0.0, 0.0, 1.0), time: 0.0, differentials: None };
+
+    #[test]
+    fn lone_triangles_have_no_crossings() {
+        let triangle: Triangle = Triangle { vertices: [V3!(-1.0, -1.0, 0.0), V3!(1.0, -1.0, 0.0), V3!(0.0, 1.0, 0.0)], material: grey() };
+        assert!(triangle.hit(&RAY, 0.001, f32::INFINITY).is_some());
+        assert!(!triangle.is_closed());
+        assert!(triangle.crossings(&RAY).is_empty());
+    }
+
+    #[test]
+    fn only_closed_meshes_have_crossings() {
+        let closed: Arc<TriangleMesh> = tetrahedron(vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]]);
+        assert!(closed.closed);
+        let crossings: Vec<Hit> = TriangleMesh::triangles(&closed).flat_map(|triangle: MeshTriangle| triangle.crossings(&RAY)).collect();
+        assert_eq!(crossings.len(), 2);
+        assert_eq!(crossings.iter().filter(|hit: &&Hit| hit.outward).count(), 1);
+
+        let open: Arc<TriangleMesh> = tetrahedron(vec![[0, 2, 1], [0, 1, 3], [0, 3, 2]]);
+        assert!(!open.closed);
+        assert!(TriangleMesh::triangles(&open).all(|triangle: MeshTriangle| triangle.crossings(&RAY).is_empty()));
+    }
+}
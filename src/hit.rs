@@ -1,39 +1,228 @@
-use std::rc::Rc;
+use std::f32::consts::PI;
+use std::sync::Arc;
+use crate::colour;
 use crate::vector_3::{Vector3, Point3};
-use crate::ray::Ray;
+use crate::ray::{Ray, Differentials};
 use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::light::tree::LightBounds;
 
-//Position, Normal, Distance, Outward
-pub enum HitResult {
-    Hit(Point3, Vector3, f32, Rc<dyn Material>, bool),
-    None,
+pub struct Hit {
+    pub point: Point3,
+    //SHADING NORMAL, SMOOTHED ON MESHES WITH VERTEX NORMALS, BOTH NORMALS FACE THE RAY
+    pub normal: Vector3,
+    pub geometric_normal: Vector3,
+    //UNIT, PERPENDICULAR TO normal AND AS CLOSE AS POSSIBLE TO THE DIRECTION OF INCREASING U
+    pub tangent: Vector3,
+    pub distance: f32,
+    pub material: Arc<dyn Material>,
+    //THE RAY ARRIVED FROM OUTSIDE THE SURFACE
+    pub outward: bool,
+    pub uv: (f32, f32),
+    //INDEX OF THE TRIANGLE IN ITS MESH, 0 FOR EVERY OTHER SURFACE
+    pub primitive: usize,
+    //OUTERMOST INSTANCE THE RAY WENT THROUGH
+    pub instance: Option<usize>,
+    //OFFSETS FROM point TO WHERE THE NEIGHBOURING PIXELS' RAYS MEET THE TANGENT PLANE
+    pub differentials: Option<(Vector3, Vector3)>,
 }
 
-pub trait Surface {
-    fn hit(&self, ray: &Ray, min: f32, max: f32) -> HitResult;
+impl Hit {
+    //outward_normal IS FLIPPED TO FACE THE RAY, tangent NEED NOT BE PERPENDICULAR OR UNIT
+    pub fn new(ray: &Ray, distance: f32, outward_normal: Vector3, material: Arc<dyn Material>, uv: (f32, f32), tangent: Vector3) -> Hit {
+        let outward: bool = Vector3::dot(&ray.direction, &outward_normal) < 0.0;
+        let normal: Vector3 = if outward {outward_normal} else {-outward_normal};
+        Hit {
+            point: ray.at(distance),
+            normal,
+            geometric_normal: normal,
+            tangent: orthogonal(&tangent, &normal),
+            distance,
+            material,
+            outward,
+            uv,
+            primitive: 0,
+            instance: None,
+            differentials: None,
+        }
+    }
+
+    //SHADING NORMAL ON THE SAME SIDE AS THE GEOMETRIC ONE, THE TANGENT FOLLOWS IT
+    pub fn set_shading_normal(&mut self, normal: Vector3) {
+        let normal: Vector3 = normal.unit_vector();
+        self.normal = if Vector3::dot(&normal, &self.geometric_normal) < 0.0 {-normal} else {normal};
+        self.tangent = orthogonal(&self.tangent, &self.normal);
+    }
+
+    //WHERE THE NEIGHBOURING RAYS CROSS THE PLANE THROUGH point
+    pub fn footprint(&self, differentials: &Differentials) -> Option<(Vector3, Vector3)> {
+        let offset = |axis: usize| -> Option<Vector3> {
+            let (origin, direction): (Point3, Vector3) = (differentials.origin[axis], differentials.direction[axis]);
+            let denominator: f32 = Vector3::dot(&self.geometric_normal, &direction);
+            if denominator.abs() < 1e-8 {
+                return None;
+            }
+            let t: f32 = Vector3::dot(&self.geometric_normal, &(self.point - origin)) / denominator;
+            Some(origin + t * direction - self.point)
+        };
+        Some((offset(0)?, offset(1)?))
+    }
+
+    //LONGER SIDE OF THE PIXEL FOOTPRINT, ZERO WHEN THE RAY CARRIED NO DIFFERENTIALS
+    pub fn footprint_width(&self) -> f32 {
+        self.differentials.map_or(0.0, |(dx, dy): (Vector3, Vector3)| f32::max(dx.length(), dy.length()))
+    }
+}
+
+//GRAM-SCHMIDT, FALLING BACK TO ANY PERPENDICULAR WHEN tangent IS PARALLEL TO normal
+fn orthogonal(tangent: &Vector3, normal: &Vector3) -> Vector3 {
+    let projected: Vector3 = *tangent - Vector3::dot(tangent, normal) * *normal;
+    if projected.length_squared() > 1e-12 {projected.unit_vector()} else {Vector3::orthonormal_basis(normal).0}
+}
+
+pub trait Surface: Send + Sync {
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit>;
+
+    //hit FOR THE RAYS OF A PATH, PARTICIPATING MEDIA DRAW WHERE THEY SCATTER FROM rng
+    fn hit_random(&self, ray: &Ray, min: f32, max: f32, _rng: &mut xorshift::StdRng) -> Option<Hit> {
+        self.hit(ray, min, max)
+    }
+
+    //NONE FOR UNBOUNDED SURFACES
+    fn bounding_box(&self) -> Option<Aabb>;
+
+    //DIRECTION FROM origin TOWARDS A RANDOM POINT ON THE SURFACE, FOR SURFACES THAT CAN BE LIGHTS
+    fn sample(&self, _origin: &Point3, _rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        None
+    }
+
+    //SOLID ANGLE DENSITY OF sample PRODUCING direction
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vector3) -> f32 {
+        0.0
+    }
+
+    //ENCLOSES A VOLUME, SO ITS crossings ALTERNATE BETWEEN ENTERING AND LEAVING
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    //FLUX LEAVING AN EMISSIVE SURFACE, FOR PICKING LIGHTS IN PROPORTION TO IT
+    fn power(&self) -> f32 {
+        0.0
+    }
+
+    //WHERE AN EMISSIVE SURFACE IS AND WHICH WAY IT FACES, NONE IF IT CANNOT BE SAMPLED
+    fn light_bounds(&self) -> Option<LightBounds> {
+        None
+    }
+
+    //EVERY HIT ALONG THE WHOLE LINE OF THE RAY, IN ANY ORDER, OUTWARD ONES ENTER AND THE OTHERS LEAVE
+    //EMPTY FOR SURFACES THAT DO NOT ENCLOSE A VOLUME
+    fn crossings(&self, _ray: &Ray) -> Vec<Hit> {
+        vec![]
+    }
+}
+
+impl<T: Surface + ?Sized> Surface for Arc<T> {
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        (**self).hit(ray, min, max)
+    }
+
+    fn hit_random(&self, ray: &Ray, min: f32, max: f32, rng: &mut xorshift::StdRng) -> Option<Hit> {
+        (**self).hit_random(ray, min, max, rng)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        (**self).bounding_box()
+    }
+
+    fn sample(&self, origin: &Point3, rng: &mut xorshift::StdRng) -> Option<Vector3> {
+        (**self).sample(origin, rng)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3) -> f32 {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn is_closed(&self) -> bool {
+        (**self).is_closed()
+    }
+
+    fn power(&self) -> f32 {
+        (**self).power()
+    }
+
+    fn light_bounds(&self) -> Option<LightBounds> {
+        (**self).light_bounds()
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        (**self).crossings(ray)
+    }
+}
+
+//SOLID ANGLE DENSITY OF A UNIFORMLY SAMPLED AREA, SEEN ALONG direction AT distance
+pub fn area_pdf(direction: &Vector3, distance: f32, normal: &Vector3, area: f32) -> f32 {
+    let distance_squared: f32 = distance * distance * direction.length_squared();
+    let cosine: f32 = Vector3::dot(&direction.unit_vector(), normal).abs();
+    if cosine < 1e-6 {0.0} else {distance_squared / (cosine * area)}
+}
+
+//LAMBERTIAN EMITTER OF area SEEN FROM ONE SIDE
+pub fn emitted_power(material: &dyn Material, area: f32) -> f32 {
+    PI * area * colour::luminance(&material.emission())
 }
 
+#[derive(Default)]
 pub struct HitList {
     pub objects: Vec<Box<dyn Surface>>
 }
 
 impl Surface for HitList {
-    fn hit(&self, ray: &crate::ray::Ray, min: f32, max: f32) -> HitResult {
-        let mut hit_record: HitResult = HitResult::None;
+    fn hit(&self, ray: &Ray, min: f32, max: f32) -> Option<Hit> {
+        let mut closest: Option<Hit> = None;
+        let mut max: f32 = max;
+
+        for object in self.objects.iter() {
+            if let Some(hit) = object.hit(ray, min, max) {
+                max = hit.distance;
+                closest = Some(hit);
+            }
+        }
+        closest
+    }
+
+    fn hit_random(&self, ray: &Ray, min: f32, max: f32, rng: &mut xorshift::StdRng) -> Option<Hit> {
+        let mut closest: Option<Hit> = None;
         let mut max: f32 = max;
 
         for object in self.objects.iter() {
-            if let HitResult::Hit(point, normal, distance, material, outward) = object.hit(ray, min, max) {
-                max = distance;
-                hit_record = HitResult::Hit(point, normal, distance, material, outward);
+            if let Some(hit) = object.hit_random(ray, min, max, rng) {
+                max = hit.distance;
+                closest = Some(hit);
             }
         }
-        hit_record
+        closest
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut objects = self.objects.iter();
+        let first: Aabb = objects.next()?.bounding_box()?;
+        objects.try_fold(first, |bounds: Aabb, object| Some(Aabb::union(&bounds, &object.bounding_box()?)))
+    }
+
+    //EVERY PART MUST BE CLOSED, NOTHING AT ALL ENCLOSES NOTHING
+    fn is_closed(&self) -> bool {
+        !self.objects.is_empty() && self.objects.iter().all(|object| object.is_closed())
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        self.objects.iter().flat_map(|object| object.crossings(ray)).collect()
     }
 }
 
 impl HitList {
-    pub fn add(&mut self, surface: impl Surface + 'static) -> () {
+    pub fn add(&mut self, surface: impl Surface + 'static) {
         self.objects.push(Box::new(surface))
     }
 }
\ No newline at end of file
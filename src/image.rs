@@ -0,0 +1,5 @@
+pub mod ppm;
+pub mod png;
+pub mod pfm;
+pub mod hdr;
+pub mod exr;
\ No newline at end of file
@@ -0,0 +1,211 @@
+use std::io::{self, Write};
+
+use crate::render::Framebuffer;
+
+#[derive(Copy, Clone)]
+pub enum PixelType {
+    Half,
+    Float,
+}
+
+impl PixelType {
+    fn id(&self) -> i32 {
+        match self {
+            PixelType::Half => 1,
+            PixelType::Float => 2,
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            PixelType::Half => 2,
+            PixelType::Float => 4,
+        }
+    }
+}
+
+//IEEE 754 BINARY16, ROUNDING TO NEAREST EVEN
+fn to_half(value: f32) -> u16 {
+    let bits: u32 = value.to_bits();
+    let sign: u16 = ((bits >> 16) & 0x8000) as u16;
+    let exponent: i32 = ((bits >> 23) & 0xff) as i32;
+    let mantissa: u32 = bits & 0x007f_ffff;
+
+    if exponent == 0xff {
+        //INFINITY OR NAN, KEEPING NAN QUIET
+        return sign | 0x7c00 | if mantissa != 0 {0x0200} else {0};
+    }
+
+    let exponent: i32 = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        //SUBNORMAL OR ZERO
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa: u32 = mantissa | 0x0080_0000;
+        let shift: u32 = (14 - exponent) as u32;
+        let half: u32 = mantissa >> shift;
+        let remainder: u32 = mantissa & ((1 << shift) - 1);
+        let halfway: u32 = 1 << (shift - 1);
+        let round: u32 = (remainder > halfway || (remainder == halfway && half & 1 == 1)) as u32;
+        return sign | (half + round) as u16;
+    }
+
+    let half: u32 = ((exponent as u32) << 10) | (mantissa >> 13);
+    let remainder: u32 = mantissa & 0x1fff;
+    let round: u32 = (remainder > 0x1000 || (remainder == 0x1000 && half & 1 == 1)) as u32;
+    //A CARRY INTO THE EXPONENT IS STILL THE CORRECTLY ROUNDED VALUE
+    sign | (half + round) as u16
+}
+
+fn attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend(name.as_bytes());
+    header.push(0);
+    header.extend(kind.as_bytes());
+    header.push(0);
+    header.extend((value.len() as i32).to_le_bytes());
+    header.extend(value);
+}
+
+//SINGLE PART, UNCOMPRESSED SCANLINES, ONE LINE PER CHUNK
+pub fn write_exr(out: &mut impl Write, framebuffer: &Framebuffer, pixel_type: PixelType) -> io::Result<()> {
+    let width: usize = framebuffer.width as usize;
+    let height: usize = framebuffer.height as usize;
+
+    let mut header: Vec<u8> = vec![];
+    header.extend(20000630i32.to_le_bytes());
+    header.extend(2i32.to_le_bytes());
+
+    //CHANNELS MUST BE SORTED BY NAME
+    let mut channels: Vec<u8> = vec![];
+    for name in ["B", "G", "R"] {
+        channels.extend(name.as_bytes());
+        channels.push(0);
+        channels.extend(pixel_type.id().to_le_bytes());
+        channels.extend([0, 0, 0, 0]);
+        channels.extend(1i32.to_le_bytes());
+        channels.extend(1i32.to_le_bytes());
+    }
+    channels.push(0);
+    attribute(&mut header, "channels", "chlist", &channels);
+    attribute(&mut header, "compression", "compression", &[0]);
+
+    let window: Vec<u8> = [0, 0, width as i32 - 1, height as i32 - 1].iter().flat_map(|value: &i32| value.to_le_bytes()).collect();
+    attribute(&mut header, "dataWindow", "box2i", &window);
+    attribute(&mut header, "displayWindow", "box2i", &window);
+    attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+    attribute(&mut header, "pixelAspectRatio", "float", &1.0f32.to_le_bytes());
+    attribute(&mut header, "screenWindowCenter", "v2f", &[0.0f32.to_le_bytes(), 0.0f32.to_le_bytes()].concat());
+    attribute(&mut header, "screenWindowWidth", "float", &1.0f32.to_le_bytes());
+    header.push(0);
+    out.write_all(&header)?;
+
+    //OFFSET TABLE
+    let line_size: usize = 3 * width * pixel_type.size();
+    let chunk_size: usize = 8 + line_size;
+    let first: usize = header.len() + 8 * height;
+    for y in 0..height {
+        out.write_all(&((first + y * chunk_size) as u64).to_le_bytes())?;
+    }
+
+    //EXR SCANLINES RUN FROM TOP TO BOTTOM, EACH STORES ITS CHANNELS ONE AFTER THE OTHER
+    for y in 0..height {
+        let j: u16 = (height - 1 - y) as u16;
+        out.write_all(&(y as i32).to_le_bytes())?;
+        out.write_all(&(line_size as i32).to_le_bytes())?;
+
+        let mut line: Vec<u8> = Vec::with_capacity(line_size);
+        for channel in 0..3 {
+            for i in 0..framebuffer.width {
+                let pixel_colour = framebuffer.get(i, j);
+                let value: f32 = [pixel_colour.z, pixel_colour.y, pixel_colour.x][channel];
+                match pixel_type {
+                    PixelType::Half => line.extend(to_half(value).to_le_bytes()),
+                    PixelType::Float => line.extend(value.to_le_bytes()),
+                }
+            }
+        }
+        out.write_all(&line)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_3::Vector3;
+
+    //EXACT, EVERY HALF IS REPRESENTABLE AS A FLOAT
+    fn from_half(half: u16) -> f32 {
+        let sign: f32 = if half & 0x8000 != 0 {-1.0} else {1.0};
+        let exponent: i32 = ((half >> 10) & 0x1f) as i32;
+        let mantissa: f32 = (half & 0x03ff) as f32;
+        match exponent {
+            0 => sign * mantissa * 2f32.powi(-24),
+            0x1f => if mantissa == 0.0 {sign * f32::INFINITY} else {f32::NAN},
+            _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+        }
+    }
+
+    #[test]
+    fn every_half_survives_a_round_trip() {
+        for half in 0..=u16::MAX {
+            //NAN PAYLOADS ARE NOT KEPT
+            if half & 0x7c00 == 0x7c00 && half & 0x03ff != 0 {
+                continue;
+            }
+            assert_eq!(to_half(from_half(half)), half, "{:#06x}", half);
+        }
+    }
+
+    #[test]
+    fn halfway_values_round_to_even() {
+        //1 + 2^-11 LIES HALFWAY BETWEEN 1 AND THE NEXT HALF, 1 + 3 * 2^-11 BETWEEN THAT AND THE ONE AFTER
+        assert_eq!(to_half(1.0 + 2f32.powi(-11)), 0x3c00);
+        assert_eq!(to_half(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+        assert_eq!(to_half(1.0 + 1.5 * 2f32.powi(-11)), 0x3c01);
+        //THE LARGEST HALF ROUNDS UP INTO INFINITY, THE CARRY INTO THE EXPONENT TOO
+        assert_eq!(to_half(65504.0), 0x7bff);
+        assert_eq!(to_half(65519.0), 0x7bff);
+        assert_eq!(to_half(65520.0), 0x7c00);
+        assert_eq!(to_half(2.0 - 2f32.powi(-12)), 0x4000);
+    }
+
+    #[test]
+    fn small_values_become_denormals_or_zero() {
+        assert_eq!(to_half(2f32.powi(-14)), 0x0400);
+        assert_eq!(to_half(1023.0 * 2f32.powi(-24)), 0x03ff);
+        assert_eq!(to_half(2f32.powi(-24)), 0x0001);
+        //HALFWAY BETWEEN ZERO AND THE SMALLEST DENORMAL, AND JUST ABOVE IT
+        assert_eq!(to_half(2f32.powi(-25)), 0x0000);
+        assert_eq!(to_half(1.5 * 2f32.powi(-25)), 0x0001);
+        assert_eq!(to_half(2.5 * 2f32.powi(-24)), 0x0002);
+        assert_eq!(to_half(2f32.powi(-30)), 0x0000);
+        assert_eq!(to_half(-2f32.powi(-30)), 0x8000);
+        assert_eq!(to_half(-0.0), 0x8000);
+    }
+
+    #[test]
+    fn special_values_stay_special() {
+        assert_eq!(to_half(f32::INFINITY), 0x7c00);
+        assert_eq!(to_half(f32::NEG_INFINITY), 0xfc00);
+        assert_eq!(to_half(1e10), 0x7c00);
+        assert!(from_half(to_half(f32::NAN)).is_nan());
+    }
+
+    #[test]
+    fn half_scanlines_hold_the_pixels() {
+        let framebuffer: Framebuffer = Framebuffer { width: 2, height: 1, pixels: vec![V3!(0.5, 1.0, 2.0), V3!(3.0, 1e-6, 0.0)] };
+        let mut out: Vec<u8> = vec![];
+        write_exr(&mut out, &framebuffer, PixelType::Half).unwrap();
+        //THE ONLY SCANLINE ENDS THE FILE, B THEN G THEN R FOR BOTH PIXELS
+        let line: Vec<f32> = out[out.len() - 12..].chunks(2).map(|bytes: &[u8]| from_half(u16::from_le_bytes([bytes[0], bytes[1]]))).collect();
+        let decoded = |i: usize| -> Vector3 { V3!(line[4 + i], line[2 + i], line[i]) };
+        for (i, pixel) in framebuffer.pixels.iter().enumerate() {
+            assert!((decoded(i) - *pixel).length() <= 1e-3 * pixel.length(), "{:?} is not {:?}", decoded(i), pixel);
+        }
+    }
+}
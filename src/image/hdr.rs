@@ -0,0 +1,164 @@
+use std::io::{self, Write};
+
+use crate::colour::Colour;
+use crate::render::Framebuffer;
+
+//SHARED EXPONENT, MANTISSAS SCALED SO THE LARGEST COMPONENT FILLS [128, 256)
+fn rgbe(pixel_colour: &Colour) -> [u8; 4] {
+    let largest: f32 = f32::max(pixel_colour.x, f32::max(pixel_colour.y, pixel_colour.z));
+    if largest.is_nan() || largest < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+    let largest: f32 = f32::min(largest, f32::MAX);
+    let mut exponent: i32 = largest.log2().floor() as i32 + 1;
+    if largest / 2f32.powi(exponent) >= 1.0 {
+        exponent += 1;
+    }
+    let scale: f32 = 256.0 / 2f32.powi(exponent);
+    let mantissa = |value: f32| -> u8 { (f32::max(value, 0.0) * scale) as u8 };
+    [mantissa(pixel_colour.x), mantissa(pixel_colour.y), mantissa(pixel_colour.z), (exponent + 128).clamp(0, 255) as u8]
+}
+
+//RUNS OF AT LEAST MIN_RUN EQUAL BYTES ARE ENCODED, EVERYTHING ELSE IS COPIED IN DUMPS
+const MIN_RUN: usize = 4;
+
+fn write_rle(out: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    let mut position: usize = 0;
+    while position < data.len() {
+        //FIND THE NEXT RUN
+        let mut run_start: usize = position;
+        let mut run_length: usize = 0;
+        while run_start < data.len() {
+            run_length = data[run_start..].iter().take(127).take_while(|byte: &&u8| **byte == data[run_start]).count();
+            if run_length >= MIN_RUN {
+                break;
+            }
+            run_start += run_length;
+        }
+        if run_length < MIN_RUN {
+            run_start = data.len();
+        }
+
+        //DUMP EVERYTHING BEFORE IT
+        while position < run_start {
+            let count: usize = usize::min(128, run_start - position);
+            out.write_all(&[count as u8])?;
+            out.write_all(&data[position..position + count])?;
+            position += count;
+        }
+
+        if run_start < data.len() {
+            out.write_all(&[128 + run_length as u8, data[run_start]])?;
+            position = run_start + run_length;
+        }
+    }
+    Ok(())
+}
+
+//RADIANCE RGBE, NEW STYLE RUN LENGTH ENCODED SCANLINES
+pub fn write_hdr(out: &mut impl Write, framebuffer: &Framebuffer) -> io::Result<()> {
+    write!(out, "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n", framebuffer.height, framebuffer.width)?;
+
+    let width: usize = framebuffer.width as usize;
+    for j in (0..framebuffer.height).rev() {
+        let pixels: Vec<[u8; 4]> = (0..framebuffer.width).map(|i: u16| rgbe(&framebuffer.get(i, j))).collect();
+
+        //RLE ONLY APPLIES TO SCANLINES BETWEEN 8 AND 32767 PIXELS
+        if !(8..0x8000).contains(&width) {
+            for pixel in pixels.iter() {
+                out.write_all(pixel)?;
+            }
+            continue;
+        }
+
+        out.write_all(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8])?;
+        for component in 0..4 {
+            let data: Vec<u8> = pixels.iter().map(|pixel: &[u8; 4]| pixel[component]).collect();
+            write_rle(out, &data)?;
+        }
+    }
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::colour::Transfer;
+    use crate::import::image::load_image;
+    use crate::vector_3::Vector3;
+
+    //WRITES THE FRAMEBUFFER AS AN HDR AND READS IT BACK WITH THE IMPORTER
+    fn round_trip(name: &str, framebuffer: &Framebuffer) -> Framebuffer {
+        let mut out: Vec<u8> = vec![];
+        write_hdr(&mut out, framebuffer).unwrap();
+        let directory: PathBuf = std::env::temp_dir().join(format!("raytracer-hdr-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path: PathBuf = directory.join(name);
+        std::fs::write(&path, out).unwrap();
+        load_image(&path, Transfer::Linear).unwrap()
+    }
+
+    fn assert_matches(read: &Framebuffer, written: &Framebuffer) {
+        assert_eq!((read.width, read.height), (written.width, written.height));
+        for (read, written) in read.pixels.iter().zip(written.pixels.iter()) {
+            //EIGHT BITS OF MANTISSA FOR THE LARGEST COMPONENT
+            let largest: f32 = f32::max(written.x, f32::max(written.y, written.z));
+            assert!((*read - *written).length() <= largest / 64.0, "{:?} is not {:?}", read, written);
+        }
+    }
+
+    //A FEW LONG RUNS BETWEEN STRETCHES THAT CHANGE EVERY PIXEL
+    fn stripes(width: u16, height: u16) -> Framebuffer {
+        let pixels: Vec<Colour> = (0..height as usize * width as usize)
+            .map(|index: usize| {
+                let column: usize = index % width as usize;
+                if column % 200 < 150 {V3!(0.25, 0.5, 4.0)} else {V3!(column as f32 / 16.0, 0.1, (index % 7) as f32)}
+            })
+            .collect();
+        Framebuffer { width, height, pixels }
+    }
+
+    #[test]
+    fn long_runs_are_split_at_127() {
+        let mut out: Vec<u8> = vec![];
+        write_rle(&mut out, &[7; 300]).unwrap();
+        assert_eq!(out, vec![128 + 127, 7, 128 + 127, 7, 128 + 46, 7]);
+    }
+
+    #[test]
+    fn short_runs_are_dumped() {
+        let mut out: Vec<u8> = vec![];
+        let data: Vec<u8> = (0..300).map(|index: usize| (index % 3) as u8).collect();
+        write_rle(&mut out, &data).unwrap();
+        assert_eq!(out.len(), 300 + 3);
+        assert_eq!((out[0], out[129], out[258]), (128, 128, 44));
+    }
+
+    #[test]
+    fn encoded_scanlines_round_trip() {
+        let framebuffer: Framebuffer = stripes(700, 3);
+        assert_matches(&round_trip("stripes.hdr", &framebuffer), &framebuffer);
+    }
+
+    #[test]
+    fn scanlines_outside_the_encodable_widths_are_flat() {
+        for (name, width) in [("narrow.hdr", 7), ("single.hdr", 1), ("wide.hdr", 0x8000)] {
+            let framebuffer: Framebuffer = stripes(width, 2);
+            let mut out: Vec<u8> = vec![];
+            write_hdr(&mut out, &framebuffer).unwrap();
+            let header: usize = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X {}\n", width).len();
+            assert_eq!(out.len(), header + 4 * 2 * width as usize);
+            assert_matches(&round_trip(name, &framebuffer), &framebuffer);
+        }
+        let framebuffer: Framebuffer = stripes(0x7fff, 1);
+        assert_matches(&round_trip("widest.hdr", &framebuffer), &framebuffer);
+    }
+
+    #[test]
+    fn black_and_bright_pixels_round_trip() {
+        let pixels: Vec<Vector3> = vec![Colour::ZERO, V3!(1e6, 2e5, 0.0), V3!(1e-20, 3e-20, 2e-20), V3!(1.0, 1.0, 1.0), V3!(0.0, 0.0, 255.9), Colour::ZERO, V3!(1.0, 0.5, 0.25), V3!(7.0, 7.0, 7.0)];
+        let framebuffer: Framebuffer = Framebuffer { width: 8, height: 1, pixels };
+        assert_matches(&round_trip("extremes.hdr", &framebuffer), &framebuffer);
+    }
+}
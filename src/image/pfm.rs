@@ -0,0 +1,17 @@
+use std::io::{self, Write};
+
+use crate::render::Framebuffer;
+
+//LITTLE ENDIAN, SCANLINES FROM BOTTOM TO TOP
+pub fn write_pfm(out: &mut impl Write, framebuffer: &Framebuffer) -> io::Result<()> {
+    write!(out, "PF\n{} {}\n-1.0\n", framebuffer.width, framebuffer.height)?;
+    for j in 0..framebuffer.height {
+        for i in 0..framebuffer.width {
+            let pixel_colour = framebuffer.get(i, j);
+            for value in [pixel_colour.x, pixel_colour.y, pixel_colour.z] {
+                out.write_all(&value.to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use std::io::{self, Write};
+
+use crate::colour::{self, Transfer};
+use crate::render::Framebuffer;
+
+#[derive(Copy, Clone)]
+pub enum BitDepth {
+    Eight,
+    Sixteen,
+}
+
+pub fn write_png(out: &mut impl Write, framebuffer: &Framebuffer, transfer: Transfer, depth: BitDepth) -> io::Result<()> {
+    let mut encoder: png::Encoder<&mut _> = png::Encoder::new(out, framebuffer.width as u32, framebuffer.height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(match depth {
+        BitDepth::Eight => png::BitDepth::Eight,
+        BitDepth::Sixteen => png::BitDepth::Sixteen,
+    });
+
+    let mut data: Vec<u8> = vec![];
+    for j in (0..framebuffer.height).rev() {
+        for i in 0..framebuffer.width {
+            let pixel_colour: colour::Colour = framebuffer.get(i, j);
+            match depth {
+                BitDepth::Eight => data.extend(colour::quantise_8(&pixel_colour, transfer)),
+                //PNG SAMPLES ARE BIG ENDIAN
+                BitDepth::Sixteen => data.extend(colour::quantise_16(&pixel_colour, transfer).iter().flat_map(|value: &u16| value.to_be_bytes())),
+            }
+        }
+    }
+
+    let mut writer: png::Writer<&mut _> = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&data).map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use std::io::{self, Write};
+
+use crate::colour::{self, Transfer};
+use crate::render::Framebuffer;
+
+//BINARY P6
+pub fn write_ppm(out: &mut impl Write, framebuffer: &Framebuffer, transfer: Transfer) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", framebuffer.width, framebuffer.height)?;
+    for j in (0..framebuffer.height).rev() {
+        for i in 0..framebuffer.width {
+            out.write_all(&colour::quantise_8(&framebuffer.get(i, j), transfer))?;
+        }
+    }
+    Ok(())
+}
+
+//ASCII P3
+pub fn write_ppm_ascii(out: &mut impl Write, framebuffer: &Framebuffer, transfer: Transfer) -> io::Result<()> {
+    writeln!(out, "P3\n{} {}", framebuffer.width, framebuffer.height)?;
+    writeln!(out, "255")?;
+    for j in (0..framebuffer.height).rev() {
+        for i in 0..framebuffer.width {
+            colour::write_colour(out, &framebuffer.get(i, j), transfer)?;
+        }
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,59 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub mod obj;
+pub mod mtl;
+pub mod image;
+pub mod ies;
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(PathBuf, std::io::Error),
+    Malformed(PathBuf, usize, String),
+    BadIndex(PathBuf, usize, i64),
+    UnknownMaterial(PathBuf, usize, String),
+    Unsupported(PathBuf, usize, String),
+    Image(PathBuf, String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            ImportError::Malformed(path, line, message) => write!(f, "{}:{}: {}", path.display(), line, message),
+            ImportError::BadIndex(path, line, index) => write!(f, "{}:{}: index {} is out of range", path.display(), line, index),
+            ImportError::UnknownMaterial(path, line, name) => write!(f, "{}:{}: unknown material '{}'", path.display(), line, name),
+            ImportError::Unsupported(path, line, statement) => write!(f, "{}:{}: unsupported statement '{}'", path.display(), line, statement),
+            ImportError::Image(path, message) => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+fn read(path: &Path) -> Result<String, ImportError> {
+    std::fs::read_to_string(path).map_err(|error| ImportError::Io(path.to_path_buf(), error))
+}
+
+//PARSES EXACTLY `count` FLOATS, OPTIONALLY FOLLOWED BY `optional` MORE THAT ARE IGNORED
+fn floats<'a>(path: &Path, line: usize, arguments: impl Iterator<Item = &'a str>, count: usize, optional: usize) -> Result<Vec<f32>, ImportError> {
+    let values: Vec<f32> = arguments
+        .map(|argument: &str| argument.parse::<f32>()
+            .map_err(|_| ImportError::Malformed(path.to_path_buf(), line, format!("'{}' is not a number", argument))))
+        .collect::<Result<Vec<f32>, ImportError>>()?;
+
+    if values.len() < count || values.len() > count + optional {
+        return Err(ImportError::Malformed(path.to_path_buf(), line, format!("expected {} values, found {}", count, values.len())));
+    }
+    Ok(values)
+}
+
+//WRITES contents TO A FILE OF ITS OWN IN THE TEMPORARY DIRECTORY, FOR THE PARSER TESTS
+#[cfg(test)]
+fn scratch(name: &str, contents: &str) -> PathBuf {
+    let directory: PathBuf = std::env::temp_dir().join(format!("raytracer-import-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path: PathBuf = directory.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
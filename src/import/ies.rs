@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use crate::import::{ImportError, read};
+use crate::light::profile::Profile;
+
+//IESNA LM-63 PHOTOMETRIC DATA WITH TYPE C ANGLES IN ABSOLUTE CANDELAS, TILT DATA IS READ BUT IGNORED
+pub fn load_ies(path: &Path) -> Result<Profile, ImportError> {
+    let source: String = read(path)?;
+    let malformed = |line: usize, message: String| ImportError::Malformed(path.to_path_buf(), line, message);
+    let last_line: usize = source.lines().count();
+
+    //KEYWORDS AND COMMENTS RUN UP TO THE TILT LINE
+    let mut lines = source.lines().enumerate().map(|(index, line): (usize, &str)| (index + 1, line));
+    let (tilt_line, tilt): (usize, &str) = loop {
+        match lines.next() {
+            None => return Err(malformed(last_line, "missing TILT line".to_string())),
+            Some((number, line)) => if let Some(tilt) = line.trim().strip_prefix("TILT=") {
+                break (number, tilt.trim());
+            }
+        }
+    };
+
+    //EVERYTHING AFTER IT IS NUMBERS, SEPARATED BY WHITESPACE OR COMMAS
+    let mut tokens = lines.flat_map(|(number, line): (usize, &str)| {
+        line.split(|c: char| c.is_whitespace() || c == ',').filter(|token: &&str| !token.is_empty()).map(move |token: &str| (number, token))
+    });
+    let mut values = |count: usize| -> Result<(usize, Vec<f32>), ImportError> {
+        let mut first: usize = last_line;
+        let mut values: Vec<f32> = Vec::with_capacity(count);
+        for index in 0..count {
+            let (number, token): (usize, &str) = tokens.next().ok_or(malformed(last_line, "unexpected end of file".to_string()))?;
+            if index == 0 {
+                first = number;
+            }
+            values.push(token.parse::<f32>().map_err(|_| malformed(number, format!("'{}' is not a number", token)))?);
+        }
+        Ok((first, values))
+    };
+
+    match tilt {
+        "NONE" => {}
+        "INCLUDE" => {
+            let (_, header): (usize, Vec<f32>) = values(2)?;
+            values(2 * header[1] as usize)?;
+        }
+        _ => return Err(ImportError::Unsupported(path.to_path_buf(), tilt_line, format!("TILT={}", tilt))),
+    }
+
+    //LAMPS, LUMENS, MULTIPLIER, ANGLE COUNTS, PHOTOMETRIC TYPE, UNITS, SIZE, BALLAST, RESERVED, WATTS
+    let (line, header): (usize, Vec<f32>) = values(13)?;
+    let (multiplier, vertical_count, horizontal_count, photometric_type): (f32, f32, f32, f32) = (header[2], header[3], header[4], header[5]);
+    if multiplier <= 0.0 {
+        return Err(malformed(line, format!("candela multiplier {} must be positive", multiplier)));
+    }
+    if photometric_type != 1.0 {
+        return Err(malformed(line, format!("photometric type {} is not supported, only type C", photometric_type)));
+    }
+    if vertical_count < 1.0 || horizontal_count < 1.0 || vertical_count.fract() != 0.0 || horizontal_count.fract() != 0.0 {
+        return Err(malformed(line, format!("angle counts {} and {} must be positive whole numbers", vertical_count, horizontal_count)));
+    }
+    let (vertical_count, horizontal_count): (usize, usize) = (vertical_count as usize, horizontal_count as usize);
+
+    let (line, vertical): (usize, Vec<f32>) = values(vertical_count)?;
+    if vertical.windows(2).any(|pair: &[f32]| pair[0] >= pair[1]) || vertical[0] < 0.0 || vertical[vertical_count - 1] > 180.0 {
+        return Err(malformed(line, "vertical angles must ascend from 0 to 180 degrees at most".to_string()));
+    }
+    let (line, horizontal): (usize, Vec<f32>) = values(horizontal_count)?;
+    if horizontal.windows(2).any(|pair: &[f32]| pair[0] >= pair[1]) || horizontal[0] < 0.0 || horizontal[horizontal_count - 1] > 360.0 {
+        return Err(malformed(line, "horizontal angles must ascend from 0 to 360 degrees at most".to_string()));
+    }
+    let (line, candelas): (usize, Vec<f32>) = values(vertical_count * horizontal_count)?;
+    if candelas.iter().any(|candela: &f32| *candela < 0.0) || candelas.iter().all(|candela: &f32| *candela == 0.0) {
+        return Err(malformed(line, "candela values must not be negative and some must be positive".to_string()));
+    }
+    Ok(Profile::new(vertical, horizontal, candelas.iter().map(|candela: &f32| multiplier * candela).collect()))
+}
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::import::scratch;
+
+    //TWO VERTICAL ANGLES, ONE HORIZONTAL, WITH THE MULTIPLIER AND PHOTOMETRIC TYPE LEFT TO THE CALLER
+    fn fixture(multiplier: &str, photometric_type: &str) -> String {
+        format!("IESNA:LM-63-2002\n[TEST] FIXTURE\nTILT=NONE\n1 1000 {} 2 1\n{} 1 0 0 0\n1 1 100\n0, 90\n0\n200 50\n", multiplier, photometric_type)
+    }
+
+    #[test]
+    fn candelas_are_scaled_by_the_multiplier() {
+        let path: PathBuf = scratch("fixture.ies", &fixture("2", "1"));
+        let profile: Profile = load_ies(&path).unwrap();
+        assert_eq!(profile.peak(), 400.0);
+        assert_eq!(profile.value(0.0, 0.0), 400.0);
+        assert_eq!(profile.value(std::f32::consts::FRAC_PI_2, 1.0), 100.0);
+    }
+
+    #[test]
+    fn missing_files_are_io_errors() {
+        let path: PathBuf = std::env::temp_dir().join("raytracer-no-such-file.ies");
+        assert!(matches!(load_ies(&path), Err(ImportError::Io(..))));
+    }
+
+    #[test]
+    fn errors_report_their_line() {
+        let path: PathBuf = scratch("tilt.ies", "IESNA:LM-63-2002\n[TEST] FIXTURE\n");
+        assert!(matches!(load_ies(&path), Err(ImportError::Malformed(_, 2, _))));
+        let path: PathBuf = scratch("include.ies", "IESNA:LM-63-2002\nTILT=lamp.tlt\n");
+        assert!(matches!(load_ies(&path), Err(ImportError::Unsupported(_, 2, _))));
+        let path: PathBuf = scratch("multiplier.ies", &fixture("-1", "1"));
+        assert!(matches!(load_ies(&path), Err(ImportError::Malformed(_, 4, _))));
+        let path: PathBuf = scratch("type.ies", &fixture("1", "2"));
+        assert!(matches!(load_ies(&path), Err(ImportError::Malformed(_, 4, _))));
+        let path: PathBuf = scratch("number.ies", &fixture("1", "1").replace("200 50", "200 fifty"));
+        assert!(matches!(load_ies(&path), Err(ImportError::Malformed(_, 9, _))));
+        let path: PathBuf = scratch("truncated.ies", &fixture("1", "1").replace("200 50\n", "200\n"));
+        assert!(matches!(load_ies(&path), Err(ImportError::Malformed(_, 9, _))));
+    }
+}
@@ -0,0 +1,260 @@
+use std::path::Path;
+
+use crate::colour::{Colour, Transfer};
+use crate::import::ImportError;
+use crate::render::Framebuffer;
+use crate::vector_3::Vector3;
+
+//ROWS FROM TOP TO BOTTOM, SAMPLES IN [0, 1] UNLESS THE FORMAT STORES LINEAR RADIANCE
+struct Decoded {
+    width: usize,
+    height: usize,
+    channels: usize,
+    samples: Vec<f32>,
+    linear: bool,
+}
+
+fn malformed(path: &Path, message: &str) -> ImportError {
+    ImportError::Image(path.to_path_buf(), message.to_string())
+}
+
+//P3 AND P6, WITH ONE OR TWO BYTES PER SAMPLE
+fn decode_ppm(path: &Path, bytes: &[u8]) -> Result<Decoded, ImportError> {
+    let mut position: usize = 0;
+    let mut header: Vec<String> = vec![];
+    while header.len() < 4 {
+        match bytes.get(position) {
+            None => return Err(malformed(path, "truncated PPM header")),
+            Some(b'#') => while position < bytes.len() && bytes[position] != b'\n' { position += 1 },
+            Some(byte) if byte.is_ascii_whitespace() => position += 1,
+            Some(_) => {
+                let start: usize = position;
+                while position < bytes.len() && !bytes[position].is_ascii_whitespace() && bytes[position] != b'#' { position += 1 }
+                header.push(String::from_utf8_lossy(&bytes[start..position]).to_string());
+            }
+        }
+    }
+
+    let binary: bool = match header[0].as_str() {
+        "P6" => true,
+        "P3" => false,
+        magic => return Err(malformed(path, &format!("unsupported PPM type '{}'", magic))),
+    };
+    let numbers: Vec<usize> = header[1..].iter()
+        .map(|token: &String| token.parse::<usize>().map_err(|_| malformed(path, &format!("'{}' is not a valid PPM dimension", token))))
+        .collect::<Result<Vec<usize>, ImportError>>()?;
+    let (width, height, maximum): (usize, usize, usize) = (numbers[0], numbers[1], numbers[2]);
+    if maximum == 0 || maximum > 65535 {
+        return Err(malformed(path, &format!("PPM maximum value {} is out of range", maximum)));
+    }
+    let count: usize = 3 * width * height;
+
+    let values: Vec<usize> = if binary {
+        //A SINGLE WHITESPACE CHARACTER SEPARATES THE HEADER FROM THE RASTER
+        let raster: &[u8] = bytes.get(position + 1..).unwrap_or(&[]);
+        let size: usize = if maximum < 256 {1} else {2};
+        if raster.len() < size * count {
+            return Err(malformed(path, "truncated PPM raster"));
+        }
+        raster.chunks(size).take(count)
+            .map(|sample: &[u8]| if size == 1 {sample[0] as usize} else {u16::from_be_bytes([sample[0], sample[1]]) as usize})
+            .collect()
+    } else {
+        let values: Vec<usize> = String::from_utf8_lossy(&bytes[position..]).split_whitespace().take(count)
+            .map(|token: &str| token.parse::<usize>().map_err(|_| malformed(path, &format!("'{}' is not a valid PPM sample", token))))
+            .collect::<Result<Vec<usize>, ImportError>>()?;
+        if values.len() < count {
+            return Err(malformed(path, "truncated PPM raster"));
+        }
+        values
+    };
+
+    let samples: Vec<f32> = values.iter().map(|value: &usize| f32::min(*value as f32 / maximum as f32, 1.0)).collect();
+    Ok(Decoded { width, height, channels: 3, samples, linear: false })
+}
+
+fn decode_png(path: &Path, bytes: &[u8]) -> Result<Decoded, ImportError> {
+    let error = |error: png::DecodingError| malformed(path, &error.to_string());
+
+    //PALETTES AND LOW BIT DEPTHS ARE EXPANDED TO 8 BITS
+    let mut decoder: png::Decoder<&[u8]> = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader: png::Reader<&[u8]> = decoder.read_info().map_err(error)?;
+    let mut buffer: Vec<u8> = vec![0; reader.output_buffer_size()];
+    let frame: png::OutputInfo = reader.next_frame(&mut buffer).map_err(error)?;
+
+    let channels: usize = frame.color_type.samples();
+    let samples: Vec<f32> = match frame.bit_depth {
+        png::BitDepth::Sixteen => buffer[..frame.buffer_size()].chunks(2)
+            .map(|sample: &[u8]| u16::from_be_bytes([sample[0], sample[1]]) as f32 / 65535.0)
+            .collect(),
+        _ => buffer[..frame.buffer_size()].iter().map(|sample: &u8| *sample as f32 / 255.0).collect(),
+    };
+    Ok(Decoded { width: frame.width as usize, height: frame.height as usize, channels, samples, linear: false })
+}
+
+//THE FIRST count WHITESPACE SEPARATED TOKENS AND WHERE THE BYTE AFTER THEM STARTS
+fn tokens(path: &Path, bytes: &[u8], count: usize) -> Result<(Vec<String>, usize), ImportError> {
+    let mut position: usize = 0;
+    let mut tokens: Vec<String> = vec![];
+    while tokens.len() < count {
+        match bytes.get(position) {
+            None => return Err(malformed(path, "truncated header")),
+            Some(byte) if byte.is_ascii_whitespace() => position += 1,
+            Some(_) => {
+                let start: usize = position;
+                while position < bytes.len() && !bytes[position].is_ascii_whitespace() { position += 1 }
+                tokens.push(String::from_utf8_lossy(&bytes[start..position]).to_string());
+            }
+        }
+    }
+    Ok((tokens, position + 1))
+}
+
+//PF AND Pf, THE SIGN OF THE SCALE GIVES THE BYTE ORDER, SCANLINES FROM BOTTOM TO TOP
+fn decode_pfm(path: &Path, bytes: &[u8]) -> Result<Decoded, ImportError> {
+    let (header, position): (Vec<String>, usize) = tokens(path, bytes, 4)?;
+    let channels: usize = match header[0].as_str() {
+        "PF" => 3,
+        "Pf" => 1,
+        magic => return Err(malformed(path, &format!("unsupported PFM type '{}'", magic))),
+    };
+    let dimension = |token: &String| token.parse::<usize>().map_err(|_| malformed(path, &format!("'{}' is not a valid PFM dimension", token)));
+    let (width, height): (usize, usize) = (dimension(&header[1])?, dimension(&header[2])?);
+    let scale: f32 = header[3].parse::<f32>().map_err(|_| malformed(path, &format!("'{}' is not a valid PFM scale", header[3])))?;
+    if scale == 0.0 || !scale.is_finite() {
+        return Err(malformed(path, &format!("PFM scale {} must be finite and not zero", scale)));
+    }
+
+    let count: usize = channels * width * height;
+    let raster: &[u8] = bytes.get(position..).unwrap_or(&[]);
+    if raster.len() < 4 * count {
+        return Err(malformed(path, "truncated PFM raster"));
+    }
+    let values: Vec<f32> = raster.chunks(4).take(count)
+        .map(|sample: &[u8]| {
+            let sample: [u8; 4] = [sample[0], sample[1], sample[2], sample[3]];
+            if scale < 0.0 {f32::from_le_bytes(sample)} else {f32::from_be_bytes(sample)}
+        })
+        .collect();
+    let samples: Vec<f32> = values.chunks(channels * width).rev().flatten().copied().collect();
+    Ok(Decoded { width, height, channels, samples, linear: true })
+}
+
+//ONE SCANLINE OF NEW STYLE RUN LENGTH ENCODED RGBE, EACH COMPONENT STORED SEPARATELY
+fn read_rle(path: &Path, bytes: &[u8], position: &mut usize, width: usize) -> Result<Vec<[u8; 4]>, ImportError> {
+    let truncated = || malformed(path, "truncated HDR raster");
+    let mut pixels: Vec<[u8; 4]> = vec![[0; 4]; width];
+    for component in 0..4 {
+        let mut column: usize = 0;
+        while column < width {
+            let count: u8 = *bytes.get(*position).ok_or_else(truncated)?;
+            *position += 1;
+            let (run, length): (bool, usize) = if count > 128 {(true, count as usize - 128)} else {(false, count as usize)};
+            if length == 0 || column + length > width {
+                return Err(malformed(path, "HDR run overflows its scanline"));
+            }
+            for pixel in pixels[column..column + length].iter_mut() {
+                pixel[component] = *bytes.get(*position).ok_or_else(truncated)?;
+                if !run {
+                    *position += 1;
+                }
+            }
+            if run {
+                *position += 1;
+            }
+            column += length;
+        }
+    }
+    Ok(pixels)
+}
+
+//RADIANCE RGBE, FLAT OR NEW STYLE RUN LENGTH ENCODED, WITH THE USUAL -Y H +X W ORIENTATION
+fn decode_hdr(path: &Path, bytes: &[u8]) -> Result<Decoded, ImportError> {
+    let mut position: usize = 0;
+    let mut line = || -> Result<String, ImportError> {
+        let start: usize = position;
+        while position < bytes.len() && bytes[position] != b'\n' { position += 1 }
+        if position >= bytes.len() {
+            return Err(malformed(path, "truncated HDR header"));
+        }
+        position += 1;
+        Ok(String::from_utf8_lossy(&bytes[start..position - 1]).to_string())
+    };
+
+    let magic: String = line()?;
+    if !magic.starts_with("#?") {
+        return Err(malformed(path, "missing HDR signature"));
+    }
+    loop {
+        let variable: String = line()?;
+        if variable.is_empty() {
+            break;
+        }
+        if let Some(format) = variable.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(malformed(path, &format!("unsupported HDR format '{}'", format)));
+            }
+        }
+    }
+    let resolution: String = line()?;
+    let (height, width): (usize, usize) = match resolution.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        ["-Y", height, "+X", width] => match (height.parse::<usize>(), width.parse::<usize>()) {
+            (Ok(height), Ok(width)) => (height, width),
+            _ => return Err(malformed(path, &format!("invalid HDR resolution '{}'", resolution))),
+        },
+        _ => return Err(malformed(path, &format!("unsupported HDR orientation '{}'", resolution))),
+    };
+
+    let mut samples: Vec<f32> = Vec::with_capacity(3 * width * height);
+    for _ in 0..height {
+        let encoded: bool = (8..0x8000).contains(&width) && bytes.get(position..position + 2) == Some(&[2, 2])
+            && bytes.get(position + 2).is_some_and(|byte: &u8| *byte & 0x80 == 0);
+        let pixels: Vec<[u8; 4]> = if encoded {
+            if bytes.get(position + 2..position + 4) != Some(&[(width >> 8) as u8, (width & 0xff) as u8]) {
+                return Err(malformed(path, "HDR scanline width does not match the image"));
+            }
+            position += 4;
+            read_rle(path, bytes, &mut position, width)?
+        } else {
+            let raster: &[u8] = bytes.get(position..position + 4 * width).ok_or_else(|| malformed(path, "truncated HDR raster"))?;
+            position += 4 * width;
+            raster.chunks(4).map(|pixel: &[u8]| [pixel[0], pixel[1], pixel[2], pixel[3]]).collect()
+        };
+        for pixel in pixels {
+            let scale: f32 = if pixel[3] == 0 {0.0} else {2f32.powi(pixel[3] as i32 - 136)};
+            samples.extend([pixel[0] as f32 * scale, pixel[1] as f32 * scale, pixel[2] as f32 * scale]);
+        }
+    }
+    Ok(Decoded { width, height, channels: 3, samples, linear: true })
+}
+
+//8 AND 16 BIT IMAGES ARE DECODED WITH transfer TO LINEAR VALUES, FLOATING POINT ONES ARE LINEAR ALREADY, ALPHA IS DROPPED
+pub fn load_image(path: &Path, transfer: Transfer) -> Result<Framebuffer, ImportError> {
+    let bytes: Vec<u8> = std::fs::read(path).map_err(|error| ImportError::Io(path.to_path_buf(), error))?;
+    let decoded: Decoded = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("ppm") => decode_ppm(path, &bytes)?,
+        Some("png") => decode_png(path, &bytes)?,
+        Some("pfm") => decode_pfm(path, &bytes)?,
+        Some("hdr") => decode_hdr(path, &bytes)?,
+        _ => return Err(malformed(path, "expected a .ppm, .png, .pfm or .hdr image")),
+    };
+
+    if decoded.width == 0 || decoded.height == 0 || decoded.width > u16::MAX as usize || decoded.height > u16::MAX as usize {
+        return Err(malformed(path, &format!("unsupported image size {}x{}", decoded.width, decoded.height)));
+    }
+
+    //GREY CHANNELS ARE REPEATED, FRAMEBUFFER ROWS START AT THE BOTTOM
+    let mut pixels: Vec<Colour> = Vec::with_capacity(decoded.width * decoded.height);
+    for row in (0..decoded.height).rev() {
+        for column in 0..decoded.width {
+            let start: usize = (row * decoded.width + column) * decoded.channels;
+            let sample = |channel: usize| -> f32 {
+                let value: f32 = decoded.samples[start + if decoded.channels < 3 {0} else {channel}];
+                if decoded.linear {f32::max(value, 0.0)} else {transfer.decode(value)}
+            };
+            pixels.push(V3!(sample(0), sample(1), sample(2)));
+        }
+    }
+    Ok(Framebuffer { width: decoded.width as u16, height: decoded.height as u16, pixels })
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::colour::{self, Colour, Transfer};
+use crate::import::{ImportError, read, floats};
+use crate::import::image::load_image;
+use crate::material::Material;
+use crate::material::lambertian::Lambertian;
+use crate::material::metallic::Metallic;
+use crate::material::dielectric::Dielectric;
+use crate::material::diffuse_light::DiffuseLight;
+use crate::texture::Texture;
+use crate::texture::solid_colour::SolidColour;
+use crate::texture::image_texture::{ImageTexture, Wrap};
+use crate::vector_3::Vector3;
+
+pub struct MtlMaterial {
+    pub diffuse: Colour,
+    pub diffuse_map: Option<Arc<dyn Texture>>,
+    pub specular: Colour,
+    pub exponent: f32,
+    pub refraction_index: f32,
+    pub dissolve: f32,
+    pub emission: Colour,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> MtlMaterial {
+        MtlMaterial {
+            diffuse: 0.8 * Colour::ONE,
+            diffuse_map: None,
+            specular: Colour::ZERO,
+            exponent: 0.0,
+            refraction_index: 1.0,
+            dissolve: 1.0,
+            emission: Colour::ZERO,
+        }
+    }
+}
+
+impl MtlMaterial {
+
+    //EMITTERS BECOME LIGHTS, TRANSPARENT SURFACES GLASS, SPECULAR DOMINATED ONES METAL, EVERYTHING ELSE DIFFUSE
+    pub fn to_material(&self) -> Arc<dyn Material> {
+        if colour::luminance(&self.emission) > 0.0 {
+            Arc::new(DiffuseLight{emit: self.emission})
+        } else if self.dissolve < 1.0 {
+            let refraction_index: f32 = if self.refraction_index > 1.0 {self.refraction_index} else {1.5};
+            Arc::new(Dielectric::new(refraction_index))
+        } else if colour::luminance(&self.specular) > colour::luminance(&self.diffuse) {
+            //BLINN-PHONG EXPONENT TO ROUGHNESS
+            let fuzziness: f32 = f32::sqrt(2.0 / (self.exponent + 2.0));
+            Arc::new(Metallic{albedo: Arc::new(SolidColour{colour: self.specular}), fuzziness})
+        } else {
+            let albedo: Arc<dyn Texture> = match &self.diffuse_map {
+                Some(map) => map.clone(),
+                None => Arc::new(SolidColour{colour: self.diffuse}),
+            };
+            Arc::new(Lambertian{albedo})
+        }
+    }
+}
+
+pub fn load_mtl(path: &Path) -> Result<HashMap<String, MtlMaterial>, ImportError> {
+    let source: String = read(path)?;
+    let mut materials: HashMap<String, MtlMaterial> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for (number, line) in source.lines().enumerate() {
+        let number: usize = number + 1;
+        let line: &str = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(statement) = tokens.next() else { continue };
+
+        if statement == "newmtl" {
+            let name: String = tokens.collect::<Vec<&str>>().join(" ");
+            if name.is_empty() {
+                return Err(ImportError::Malformed(path.to_path_buf(), number, "newmtl without a name".to_string()));
+            }
+            materials.insert(name.clone(), MtlMaterial::default());
+            current = Some(name);
+            continue;
+        }
+
+        let material: &mut MtlMaterial = match &current {
+            Some(name) => materials.get_mut(name).unwrap(),
+            None => return Err(ImportError::Malformed(path.to_path_buf(), number, format!("'{}' before any newmtl", statement))),
+        };
+
+        match statement {
+            "Kd" | "Ks" | "Ke" => {
+                let values: Vec<f32> = floats(path, number, tokens, 3, 0)?;
+                let colour: Colour = V3!(values[0], values[1], values[2]);
+                match statement {
+                    "Kd" => material.diffuse = colour,
+                    "Ks" => material.specular = colour,
+                    _ => material.emission = colour,
+                }
+            }
+            "Ns" => material.exponent = floats(path, number, tokens, 1, 0)?[0],
+            "Ni" => material.refraction_index = floats(path, number, tokens, 1, 0)?[0],
+            "d" => material.dissolve = floats(path, number, tokens, 1, 0)?[0],
+            "Tr" => material.dissolve = 1.0 - floats(path, number, tokens, 1, 0)?[0],
+            //OPTIONS BEFORE THE FILE NAME ARE IGNORED, MAPS ARE RELATIVE TO THE MTL FILE
+            "map_Kd" => {
+                let Some(name) = tokens.last() else {
+                    return Err(ImportError::Malformed(path.to_path_buf(), number, "map_Kd without a file".to_string()));
+                };
+                let image: PathBuf = path.parent().unwrap_or(Path::new("")).join(name);
+                material.diffuse_map = Some(Arc::new(ImageTexture{image: load_image(&image, Transfer::Srgb)?, wrap: Wrap::Repeat}));
+            }
+            //RECOGNISED BUT WITHOUT AN EQUIVALENT IN OUR MATERIALS
+            "Ka" | "Tf" | "illum" => {}
+            _ => return Err(ImportError::Unsupported(path.to_path_buf(), number, statement.to_string())),
+        }
+    }
+    Ok(materials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::scratch;
+
+    #[test]
+    fn statements_apply_to_the_latest_material() {
+        let path: PathBuf = scratch("materials.mtl", "# HEADER\nnewmtl matte grey\nKd 0.5 0.5 0.5\nnewmtl glass\nd 0.25\nNi 1.33\nillum 4\n");
+        let materials: HashMap<String, MtlMaterial> = load_mtl(&path).unwrap();
+        assert_eq!(materials.len(), 2);
+        assert_eq!((materials["matte grey"].diffuse - V3!(0.5, 0.5, 0.5)).length(), 0.0);
+        assert_eq!(materials["glass"].dissolve, 0.25);
+        assert_eq!(materials["glass"].refraction_index, 1.33);
+    }
+
+    #[test]
+    fn missing_files_are_io_errors() {
+        let path: PathBuf = std::env::temp_dir().join("raytracer-no-such-file.mtl");
+        assert!(matches!(load_mtl(&path), Err(ImportError::Io(..))));
+    }
+
+    #[test]
+    fn errors_report_their_line() {
+        let path: PathBuf = scratch("orphan.mtl", "\nKd 1 1 1\n");
+        assert!(matches!(load_mtl(&path), Err(ImportError::Malformed(_, 2, _))));
+        let path: PathBuf = scratch("unnamed.mtl", "newmtl a\nnewmtl\n");
+        assert!(matches!(load_mtl(&path), Err(ImportError::Malformed(_, 2, _))));
+        let path: PathBuf = scratch("count.mtl", "newmtl a\nKd 1 1 1\nKs 1 1\n");
+        assert!(matches!(load_mtl(&path), Err(ImportError::Malformed(_, 3, _))));
+        let path: PathBuf = scratch("unsupported.mtl", "newmtl a\nPr 0.5\n");
+        assert!(matches!(load_mtl(&path), Err(ImportError::Unsupported(_, 2, ref statement)) if statement == "Pr"));
+        let path: PathBuf = scratch("texture.mtl", "newmtl a\nmap_Kd no-such-texture.png\n");
+        assert!(load_mtl(&path).is_err());
+    }
+}
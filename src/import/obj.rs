@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::geometry::triangle::TriangleMesh;
+use crate::import::{ImportError, read, floats};
+use crate::import::mtl::load_mtl;
+use crate::material::Material;
+use crate::vector_3::{Vector3, Point3};
+
+//POSITION, UV AND NORMAL INDICES OF A FACE CORNER
+type Corner = (usize, Option<usize>, Option<usize>);
+
+//OBJ INDICES START AT 1, NEGATIVE ONES COUNT BACKWARDS FROM THE LAST ELEMENT
+fn resolve(path: &Path, line: usize, token: &str, count: usize) -> Result<usize, ImportError> {
+    let index: i64 = token.parse::<i64>()
+        .map_err(|_| ImportError::Malformed(path.to_path_buf(), line, format!("'{}' is not an index", token)))?;
+    let resolved: i64 = if index < 0 {count as i64 + index} else {index - 1};
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(ImportError::BadIndex(path.to_path_buf(), line, index));
+    }
+    Ok(resolved as usize)
+}
+
+fn corner(path: &Path, line: usize, token: &str, counts: (usize, usize, usize)) -> Result<Corner, ImportError> {
+    let mut parts = token.split('/');
+    let position: usize = resolve(path, line, parts.next().unwrap_or(""), counts.0)?;
+    let uv: Option<usize> = match parts.next() {
+        None | Some("") => None,
+        Some(part) => Some(resolve(path, line, part, counts.1)?),
+    };
+    let normal: Option<usize> = match parts.next() {
+        None | Some("") => None,
+        Some(part) => Some(resolve(path, line, part, counts.2)?),
+    };
+    if parts.next().is_some() {
+        return Err(ImportError::Malformed(path.to_path_buf(), line, format!("'{}' is not a face vertex", token)));
+    }
+    Ok((position, uv, normal))
+}
+
+pub fn load_obj(path: &Path, default_material: Arc<dyn Material>) -> Result<TriangleMesh, ImportError> {
+    let source: String = read(path)?;
+
+    let mut positions: Vec<Point3> = vec![];
+    let mut uvs: Vec<(f32, f32)> = vec![];
+    let mut normals: Vec<Vector3> = vec![];
+    let mut library: HashMap<String, Arc<dyn Material>> = HashMap::new();
+    let mut material: Arc<dyn Material> = default_material;
+
+    let mut corners: HashMap<Corner, usize> = HashMap::new();
+    let mut vertices: Vec<Corner> = vec![];
+    let mut indices: Vec<[usize; 3]> = vec![];
+    let mut materials: Vec<Arc<dyn Material>> = vec![];
+
+    for (number, line) in source.lines().enumerate() {
+        let number: usize = number + 1;
+        let line: &str = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(statement) = tokens.next() else { continue };
+
+        match statement {
+            "v" => {
+                let values: Vec<f32> = floats(path, number, tokens, 3, 1)?;
+                positions.push(V3!(values[0], values[1], values[2]));
+            }
+            "vt" => {
+                let values: Vec<f32> = floats(path, number, tokens, 1, 2)?;
+                uvs.push((values[0], values.get(1).copied().unwrap_or(0.0)));
+            }
+            "vn" => {
+                let values: Vec<f32> = floats(path, number, tokens, 3, 0)?;
+                let normal: Vector3 = V3!(values[0], values[1], values[2]);
+                //A ZERO NORMAL HAS NO DIRECTION TO NORMALISE TO
+                if normal.length_squared() == 0.0 {
+                    return Err(ImportError::Malformed(path.to_path_buf(), number, "normal must not be zero".to_string()));
+                }
+                normals.push(normal.unit_vector());
+            }
+            "f" => {
+                let counts: (usize, usize, usize) = (positions.len(), uvs.len(), normals.len());
+                let face: Vec<usize> = tokens
+                    .map(|token: &str| {
+                        let key: Corner = corner(path, number, token, counts)?;
+                        Ok(*corners.entry(key).or_insert_with(|| {
+                            vertices.push(key);
+                            vertices.len() - 1
+                        }))
+                    })
+                    .collect::<Result<Vec<usize>, ImportError>>()?;
+
+                if face.len() < 3 {
+                    return Err(ImportError::Malformed(path.to_path_buf(), number, format!("face has {} vertices", face.len())));
+                }
+                //FAN TRIANGULATION, FINE FOR THE CONVEX POLYGONS EXPORTERS WRITE
+                for k in 1..face.len() - 1 {
+                    indices.push([face[0], face[k], face[k + 1]]);
+                    materials.push(material.clone());
+                }
+            }
+            "mtllib" => {
+                let directory: &Path = path.parent().unwrap_or(Path::new(""));
+                for file in tokens {
+                    for (name, mtl) in load_mtl(&directory.join(file))? {
+                        library.insert(name, mtl.to_material());
+                    }
+                }
+            }
+            "usemtl" => {
+                let name: String = tokens.collect::<Vec<&str>>().join(" ");
+                material = library.get(&name)
+                    .ok_or(ImportError::UnknownMaterial(path.to_path_buf(), number, name.clone()))?
+                    .clone();
+            }
+            //GROUPING AND SMOOTHING GROUPS DO NOT AFFECT THE MESH
+            "o" | "g" | "s" => {}
+            _ => return Err(ImportError::Unsupported(path.to_path_buf(), number, statement.to_string())),
+        }
+    }
+
+    //ATTRIBUTES ARE ONLY KEPT IF EVERY VERTEX HAS THEM
+    let has_uvs: bool = vertices.iter().all(|(_, uv, _)| uv.is_some());
+    let has_normals: bool = vertices.iter().all(|(_, _, normal)| normal.is_some());
+
+    Ok(TriangleMesh::new(
+        vertices.iter().map(|(position, _, _)| positions[*position]).collect(),
+        if has_normals {vertices.iter().map(|(_, _, normal)| normals[normal.unwrap()]).collect()} else {vec![]},
+        if has_uvs {vertices.iter().map(|(_, uv, _)| uvs[uv.unwrap()]).collect()} else {vec![]},
+        indices,
+        materials,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::import::scratch;
+    use crate::material::lambertian::Lambertian;
+    use crate::texture::solid_colour::SolidColour;
+
+    fn grey() -> Arc<dyn Material> {
+        Arc::new(Lambertian { albedo: Arc::new(SolidColour { colour: V3!(0.5, 0.5, 0.5) }) })
+    }
+
+    const SQUARE: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n";
+
+    #[test]
+    fn negative_indices_count_back_from_the_last_vertex() {
+        let path: PathBuf = scratch("negative.obj", &format!("{}f -4 -3 -2\n", SQUARE));
+        let mesh: TriangleMesh = load_obj(&path, grey()).unwrap();
+        assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+        assert_eq!((mesh.positions[2] - V3!(1.0, 1.0, 0.0)).length(), 0.0);
+    }
+
+    #[test]
+    fn polygons_are_split_into_a_fan() {
+        let path: PathBuf = scratch("pentagon.obj", &format!("{}v 0.5 1.5 0\nf 1 2 3 5 4\n", SQUARE));
+        let mesh: TriangleMesh = load_obj(&path, grey()).unwrap();
+        assert_eq!(mesh.indices, vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]]);
+        assert_eq!(mesh.materials.len(), 3);
+        assert_eq!((mesh.positions[3] - V3!(0.5, 1.5, 0.0)).length(), 0.0);
+    }
+
+    #[test]
+    fn missing_files_are_io_errors() {
+        let path: PathBuf = std::env::temp_dir().join("raytracer-no-such-file.obj");
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::Io(..))));
+    }
+
+    #[test]
+    fn out_of_range_indices_report_their_line() {
+        let path: PathBuf = scratch("range.obj", &format!("{}\n# COMMENT\nf 1 2 5\n", SQUARE));
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::BadIndex(_, 7, 5))));
+        let path: PathBuf = scratch("zero.obj", &format!("{}f 0 1 2\n", SQUARE));
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::BadIndex(_, 5, 0))));
+        let path: PathBuf = scratch("before.obj", &format!("{}f -5 1 2\n", SQUARE));
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::BadIndex(_, 5, -5))));
+    }
+
+    #[test]
+    fn zero_normals_are_rejected() {
+        let path: PathBuf = scratch("normal.obj", &format!("{}vn 0 0 1\nvn 0 0 0\nf 1//1 2//1 3//2\n", SQUARE));
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::Malformed(_, 6, _))));
+    }
+
+    #[test]
+    fn malformed_lines_report_their_line() {
+        let path: PathBuf = scratch("short.obj", &format!("{}f 1 2\n", SQUARE));
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::Malformed(_, 5, _))));
+        let path: PathBuf = scratch("number.obj", "v 0 0 0\nv 1 x 0\n");
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::Malformed(_, 2, _))));
+        let path: PathBuf = scratch("statement.obj", "v 0 0 0\ncurv 0 1 1 2\n");
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::Unsupported(_, 2, _))));
+    }
+
+    #[test]
+    fn materials_come_from_the_library_beside_the_file() {
+        scratch("library.mtl", "newmtl red\nKd 1 0 0\n");
+        let path: PathBuf = scratch("library.obj", &format!("mtllib library.mtl\n{}usemtl red\nf 1 2 3\nusemtl blue\nf 1 3 4\n", SQUARE));
+        assert!(matches!(load_obj(&path, grey()), Err(ImportError::UnknownMaterial(_, 8, ref name)) if name == "blue"));
+    }
+}
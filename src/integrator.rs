@@ -0,0 +1,130 @@
+use crate::background::Background;
+use crate::colour::Colour;
+use crate::hit::{Surface, Hit};
+use crate::light::{Lights, LightSample};
+use crate::medium::Medium;
+use crate::material::Scatter;
+use crate::ray::Ray;
+use crate::vector_3::Vector3;
+use xorshift::Rng;
+
+const MIN_DISTANCE: f32 = 0.005;
+const MAX_DISTANCE: f32 = 1024.0;
+
+//PATH TRACER WITH NEXT EVENT ESTIMATION, LIGHT AND BSDF SAMPLES ARE COMBINED WITH THE POWER HEURISTIC
+pub struct Integrator<'a> {
+    pub world: &'a dyn Surface,
+    pub lights: &'a Lights,
+    pub background: &'a dyn Background,
+    //FILLS THE SCENE UP TO MAX_DISTANCE
+    pub medium: Option<&'a Medium>,
+    pub depth: u16,
+}
+
+fn power_heuristic(pdf: f32, other: f32) -> f32 {
+    let pdf: f32 = pdf * pdf;
+    let other: f32 = other * other;
+    if pdf + other > 0.0 {pdf / (pdf + other)} else {0.0}
+}
+
+fn is_black(colour: &Colour) -> bool {
+    colour.x <= 0.0 && colour.y <= 0.0 && colour.z <= 0.0
+}
+
+//WHICH WAY THE SURFACE AT hit FACES, FOR PICKING LIGHTS, NONE WHERE A MEDIUM SCATTERED
+fn facing(hit: &Hit) -> Option<&Vector3> {
+    if hit.material.volumetric() {None} else {Some(&hit.normal)}
+}
+
+impl Integrator<'_> {
+
+    pub fn ray_colour(&self, ray: &Ray, rng: &mut xorshift::StdRng) -> Colour {
+        self.radiance(ray, self.depth, 1.0, rng)
+    }
+
+    //emission_weight IS THE MIS WEIGHT OF EMITTERS THIS RAY FINDS, 1 UNLESS LIGHT SAMPLING COULD HAVE FOUND THEM TOO
+    fn radiance(&self, ray: &Ray, depth: u16, emission_weight: f32, rng: &mut xorshift::StdRng) -> Colour {
+
+        match self.trace(ray, rng) {
+            Some(hit) => {
+                let emitted: Colour = emission_weight * hit.material.emitted(&hit);
+                if depth == 0 {
+                    return emitted;
+                }
+                match hit.material.scatter(ray, &hit, rng) {
+                    Scatter::None => emitted,
+                    Scatter::Specular(attenuation, scatter_ray) => {
+                        emitted + attenuation * self.radiance(&scatter_ray, depth - 1, 1.0, rng)
+                    }
+                    Scatter::Sampled(attenuation, scatter_ray, pdf) => {
+                        if self.lights.is_empty() {
+                            return emitted + attenuation * self.radiance(&scatter_ray, depth - 1, 1.0, rng);
+                        }
+                        let direct: Colour = self.direct(ray, &hit, rng);
+                        let light_pdf: f32 = self.lights.pdf(&hit.point, facing(&hit), &scatter_ray.direction);
+                        let weight: f32 = power_heuristic(pdf, light_pdf);
+                        emitted + direct + attenuation * self.radiance(&scatter_ray, depth - 1, weight, rng)
+                    }
+                }
+            }
+            None => emission_weight * self.background.colour(&ray.direction)
+        }
+    }
+
+    //CLOSEST SURFACE, OR WHERE THE RAY SCATTERS IN THE GLOBAL MEDIUM IF THAT COMES FIRST
+    fn trace(&self, ray: &Ray, rng: &mut xorshift::StdRng) -> Option<Hit> {
+        let mut hit: Hit = match self.medium {
+            None => self.world.hit_random(ray, MIN_DISTANCE, MAX_DISTANCE, rng)?,
+            Some(medium) => {
+                let flight: f32 = medium.sample_distance(rng.next_f32()) / ray.direction.length();
+                match self.world.hit_random(ray, MIN_DISTANCE, f32::min(flight, MAX_DISTANCE), rng) {
+                    None if flight < MAX_DISTANCE => {
+                        Hit::new(ray, flight, -ray.direction.unit_vector(), medium.phase.clone(), (0.0, 0.0), Vector3::ZERO)
+                    }
+                    hit => hit?,
+                }
+            }
+        };
+        hit.differentials = ray.differentials.and_then(|differentials| hit.footprint(&differentials));
+        Some(hit)
+    }
+
+    //ONE LIGHT SAMPLE AND ITS SHADOW RAY
+    fn direct(&self, ray: &Ray, hit: &Hit, rng: &mut xorshift::StdRng) -> Colour {
+        let transmittance = |distance: f32, direction: &Vector3| -> f32 {
+            self.medium.map_or(1.0, |medium: &Medium| medium.transmittance(distance * direction.length()))
+        };
+        let direction: Vector3 = match self.lights.sample(&hit.point, facing(hit), rng) {
+            None => return Colour::ZERO,
+            Some(LightSample::Direction(direction)) => direction,
+            //NOTHING BUT A SHADOW RAY CAN FIND A PUNCTUAL LIGHT, SO THERE IS NOTHING TO WEIGH IT AGAINST
+            Some(LightSample::Punctual(direction, distance, irradiance)) => {
+                let bsdf: Colour = hit.material.eval(ray, hit, &direction);
+                let distance: f32 = f32::min(distance, MAX_DISTANCE);
+                let shadow_ray: Ray = Ray{ origin: hit.point, direction, time: ray.time, differentials: None };
+                if is_black(&bsdf) || self.world.hit_random(&shadow_ray, MIN_DISTANCE, distance - MIN_DISTANCE, rng).is_some() {
+                    return Colour::ZERO;
+                }
+                return transmittance(distance, &direction) * bsdf * irradiance;
+            }
+        };
+
+        let light_pdf: f32 = self.lights.pdf(&hit.point, facing(hit), &direction);
+        let bsdf: Colour = hit.material.eval(ray, hit, &direction);
+        if light_pdf <= 0.0 || is_black(&bsdf) {
+            return Colour::ZERO;
+        }
+
+        //A SHADOW RAY THAT ESCAPES SEES THE BACKGROUND, WHEN THAT IS ONE OF THE LIGHTS
+        let shadow_ray: Ray = Ray{ origin: hit.point, direction, time: ray.time, differentials: None };
+        let emitted: Colour = match self.world.hit_random(&shadow_ray, MIN_DISTANCE, MAX_DISTANCE, rng) {
+            Some(light_hit) => transmittance(light_hit.distance, &direction) * light_hit.material.emitted(&light_hit),
+            None => match &self.lights.environment {
+                Some(environment) => transmittance(MAX_DISTANCE, &direction) * environment.colour(&direction),
+                None => return Colour::ZERO,
+            },
+        };
+        let bsdf_pdf: f32 = hit.material.pdf(ray, hit, &direction);
+        (power_heuristic(light_pdf, bsdf_pdf) / light_pdf) * bsdf * emitted
+    }
+}
\ No newline at end of file
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+use xorshift::Rng;
+
+use crate::background::Background;
+use crate::colour::Colour;
+use crate::distribution::Distribution;
+use crate::hit::Surface;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+pub mod point;
+pub mod spot;
+pub mod directional;
+pub mod profile;
+pub mod tree;
+
+use tree::{BoundedLight, LightBounds, LightTree};
+
+//LIGHTS WITHOUT AREA, ONLY SHADOW RAYS CAN REACH THEM
+pub trait Light: Send + Sync {
+    //UNIT DIRECTION AND DISTANCE FROM point TO THE LIGHT, AND THE IRRADIANCE IT DELIVERS ON A SURFACE FACING IT
+    fn illuminate(&self, point: &Point3) -> Option<(Vector3, f32, Colour)>;
+
+    //FLUX, radius BOUNDS THE SCENE FOR LIGHTS AT INFINITY
+    fn power(&self, radius: f32) -> f32;
+
+    //NONE FOR LIGHTS AT INFINITY
+    fn light_bounds(&self) -> Option<LightBounds> {
+        None
+    }
+}
+
+pub enum LightSample {
+    //TOWARDS AN EMISSIVE SURFACE OR THE ENVIRONMENT, WHAT ARRIVES IS FOUND BY TRACING IT
+    Direction(Vector3),
+    //UNIT DIRECTION, DISTANCE AND IRRADIANCE OF A PUNCTUAL LIGHT, ALREADY DIVIDED BY THE CHANCE OF PICKING IT
+    Punctual(Vector3, f32, Colour),
+}
+
+//A LIGHT BY ITS INDEX, COUNTING THE SURFACES, THEN THE ENVIRONMENT, THEN THE PUNCTUAL LIGHTS
+enum Entry<'a> {
+    Surface(&'a dyn Surface),
+    Environment(&'a dyn Background),
+    Punctual(&'a dyn Light),
+}
+
+//EMISSIVE SURFACES, ALSO PRESENT IN THE WORLD, THE BACKGROUND IF IT LIGHTS THE SCENE, AND PUNCTUAL LIGHTS
+//build SORTS THEM INTO A TREE AND THE LIGHTS AT INFINITY, UNTIL THEN EVERY LIGHT IS EQUALLY LIKELY
+#[derive(Default)]
+pub struct Lights {
+    pub surfaces: Vec<Arc<dyn Surface>>,
+    pub environment: Option<Arc<dyn Background>>,
+    pub punctual: Vec<Box<dyn Light>>,
+    //INDICES OF THE LIGHTS AT INFINITY
+    infinite: Vec<usize>,
+    tree: Option<LightTree>,
+    //OVER THE LIGHTS AT INFINITY AND THEN THE TREE, BY POWER
+    selection: Option<Distribution>,
+}
+
+impl Lights {
+
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+
+    fn count(&self) -> usize {
+        self.surfaces.len() + self.environment.is_some() as usize + self.punctual.len()
+    }
+
+    fn entry(&self, index: usize) -> Entry<'_> {
+        if let Some(surface) = self.surfaces.get(index) {
+            return Entry::Surface(&**surface);
+        }
+        let index: usize = index - self.surfaces.len();
+        match &self.environment {
+            Some(environment) if index == 0 => Entry::Environment(&**environment),
+            _ => Entry::Punctual(&*self.punctual[index - self.environment.is_some() as usize]),
+        }
+    }
+
+    //radius BOUNDS THE SCENE, THE BACKGROUND AND DIRECTIONAL LIGHTS SHINE ON A DISC OF IT
+    pub fn build(&mut self, radius: f32) {
+        let area: f32 = std::f32::consts::PI * radius * radius;
+        let mut bounded: Vec<BoundedLight> = vec![];
+        let mut bounded_power: f32 = 0.0;
+        let mut infinite: Vec<(usize, f32)> = vec![];
+        for index in 0..self.count() {
+            match self.entry(index) {
+                //SURFACES WITHOUT BOUNDS CAN ONLY BE FOUND BY HITTING THEM
+                Entry::Surface(surface) => if let Some(bounds) = surface.light_bounds() {
+                    bounded_power += surface.power();
+                    bounded.push((bounds, index));
+                }
+                Entry::Environment(environment) => infinite.push((index, area * environment.power())),
+                Entry::Punctual(light) => match light.light_bounds() {
+                    Some(bounds) => {
+                        bounded_power += light.power(radius);
+                        bounded.push((bounds, index));
+                    }
+                    None => infinite.push((index, light.power(radius))),
+                },
+            }
+        }
+
+        let mut weights: Vec<f32> = infinite.iter().map(|(_, power)| *power).collect();
+        self.tree = LightTree::new(bounded);
+        if self.tree.is_some() {
+            weights.push(bounded_power);
+        }
+        self.infinite = infinite.into_iter().map(|(index, _)| index).collect();
+        self.selection = if weights.is_empty() {None} else {Some(Distribution::new(&weights))};
+    }
+
+    //normal FACES AWAY FROM THE SURFACE AT origin, LIGHTS IT FACES ARE PICKED MORE OFTEN, NONE IN A MEDIUM
+    pub fn sample(&self, origin: &Point3, normal: Option<&Vector3>, rng: &mut xorshift::StdRng) -> Option<LightSample> {
+        let (index, probability): (usize, f32) = match &self.selection {
+            None if self.is_empty() => return None,
+            None => (usize::min((rng.next_f32() * self.count() as f32) as usize, self.count() - 1), 1.0 / self.count() as f32),
+            Some(selection) => {
+                let (choice, _): (usize, f32) = selection.sample(rng.next_f32());
+                match self.infinite.get(choice) {
+                    Some(index) => (*index, selection.probability(choice)),
+                    None => {
+                        let (index, probability): (usize, f32) = self.tree.as_ref()?.sample(origin, normal, rng)?;
+                        (index, probability * selection.probability(choice))
+                    }
+                }
+            }
+        };
+        match self.entry(index) {
+            Entry::Surface(surface) => surface.sample(origin, rng).map(LightSample::Direction),
+            Entry::Environment(environment) => environment.sample(rng).map(LightSample::Direction),
+            Entry::Punctual(light) => {
+                let (direction, distance, irradiance): (Vector3, f32, Colour) = light.illuminate(origin)?;
+                Some(LightSample::Punctual(direction, distance, irradiance / probability))
+            }
+        }
+    }
+
+    //DENSITY OF THE WHOLE MIXTURE, EVERY LIGHT WITH AREA COULD HAVE PRODUCED direction
+    pub fn pdf(&self, origin: &Point3, normal: Option<&Vector3>, direction: &Vector3) -> f32 {
+        let Some(selection) = &self.selection else {
+            let density: f32 = (0..self.count())
+                .map(|index: usize| match self.entry(index) {
+                    Entry::Surface(surface) => surface.pdf_value(origin, direction),
+                    Entry::Environment(environment) => environment.pdf_value(direction),
+                    Entry::Punctual(_) => 0.0,
+                })
+                .sum();
+            return if self.is_empty() {0.0} else {density / self.count() as f32};
+        };
+        let infinite: f32 = self.infinite.iter().enumerate()
+            .map(|(choice, index)| match self.entry(*index) {
+                Entry::Environment(environment) => selection.probability(choice) * environment.pdf_value(direction),
+                _ => 0.0,
+            })
+            .sum();
+        let bounded: f32 = match &self.tree {
+            Some(tree) => {
+                let ray: Ray = Ray{ origin: *origin, direction: *direction, time: 0.0, differentials: None };
+                let density = |index: usize| -> f32 {
+                    match self.entry(index) {
+                        Entry::Surface(surface) => surface.pdf_value(origin, direction),
+                        _ => 0.0,
+                    }
+                };
+                selection.probability(self.infinite.len()) * tree.pdf(&ray, normal, &density)
+            }
+            None => 0.0,
+        };
+        infinite + bounded
+    }
+}
\ No newline at end of file
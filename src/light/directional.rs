@@ -0,0 +1,20 @@
+use crate::colour::{self, Colour};
+use crate::light::Light;
+use crate::vector_3::{Vector3, Point3};
+
+//PARALLEL LIGHT FROM INFINITELY FAR AWAY, irradiance IS MEASURED FACING IT
+pub struct Directional {
+    //UNIT, THE WAY THE LIGHT TRAVELS
+    pub direction: Vector3,
+    pub irradiance: Colour,
+}
+
+impl Light for Directional {
+    fn illuminate(&self, _point: &Point3) -> Option<(Vector3, f32, Colour)> {
+        Some((-self.direction, f32::INFINITY, self.irradiance))
+    }
+
+    fn power(&self, radius: f32) -> f32 {
+        std::f32::consts::PI * radius * radius * colour::luminance(&self.irradiance)
+    }
+}
\ No newline at end of file
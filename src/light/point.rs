@@ -0,0 +1,33 @@
+use std::f32::consts::PI;
+
+use crate::aabb::Aabb;
+use crate::colour::{self, Colour};
+use crate::light::Light;
+use crate::light::tree::LightBounds;
+use crate::vector_3::{Vector3, Point3};
+
+//SHINES intensity EQUALLY IN EVERY DIRECTION
+pub struct Point {
+    pub position: Point3,
+    pub intensity: Colour,
+}
+
+impl Light for Point {
+    fn illuminate(&self, point: &Point3) -> Option<(Vector3, f32, Colour)> {
+        let offset: Vector3 = self.position - *point;
+        let distance_squared: f32 = offset.length_squared();
+        if distance_squared == 0.0 {
+            return None;
+        }
+        let distance: f32 = distance_squared.sqrt();
+        Some((offset / distance, distance, self.intensity / distance_squared))
+    }
+
+    fn power(&self, _radius: f32) -> f32 {
+        4.0 * PI * colour::luminance(&self.intensity)
+    }
+
+    fn light_bounds(&self) -> Option<LightBounds> {
+        Some(LightBounds::omnidirectional(Aabb::new(self.position, self.position), 4.0 * PI * colour::luminance(&self.intensity)))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,55 @@
+//PHOTOMETRIC DIAGRAM OF A FIXTURE, TYPE C: VERTICAL ANGLES FROM THE AXIS IT POINTS ALONG, HORIZONTAL ANGLES AROUND IT
+//THE LAST HORIZONTAL ANGLE GIVES THE SYMMETRY, UP TO 90 THE QUADRANTS MIRROR EACH OTHER, UP TO 180 THE TWO HALVES DO
+pub struct Profile {
+    //DEGREES, ASCENDING
+    vertical: Vec<f32>,
+    horizontal: Vec<f32>,
+    //ONE RUN OF vertical CANDELAS PER HORIZONTAL ANGLE
+    values: Vec<f32>,
+    peak: f32,
+}
+
+//INDICES OF THE ANGLES EITHER SIDE OF angle AND HOW FAR IT IS BETWEEN THEM, CLAMPED TO THE ENDS
+fn bracket(angles: &[f32], angle: f32) -> (usize, usize, f32) {
+    let upper: usize = angles.partition_point(|a: &f32| *a <= angle);
+    if upper == 0 {
+        return (0, 0, 0.0);
+    }
+    if upper == angles.len() {
+        return (upper - 1, upper - 1, 0.0);
+    }
+    let lower: usize = upper - 1;
+    (lower, upper, (angle - angles[lower]) / (angles[upper] - angles[lower]))
+}
+
+impl Profile {
+    //values HOLDS vertical.len() * horizontal.len() CANDELAS
+    pub fn new(vertical: Vec<f32>, horizontal: Vec<f32>, values: Vec<f32>) -> Profile {
+        let peak: f32 = values.iter().fold(0.0, |peak: f32, value: &f32| f32::max(peak, *value));
+        Profile { vertical, horizontal, values, peak }
+    }
+
+    //BRIGHTEST INTENSITY IN ANY DIRECTION
+    pub fn peak(&self) -> f32 {
+        self.peak
+    }
+
+    //CANDELAS theta RADIANS FROM THE AXIS AND phi RADIANS AROUND IT
+    pub fn value(&self, theta: f32, phi: f32) -> f32 {
+        let theta: f32 = theta.to_degrees();
+        if theta < self.vertical[0] || theta > self.vertical[self.vertical.len() - 1] {
+            return 0.0;
+        }
+        let phi: f32 = phi.to_degrees().rem_euclid(360.0);
+        let last: f32 = self.horizontal[self.horizontal.len() - 1];
+        let phi: f32 = if last <= 180.0 && phi > 180.0 {360.0 - phi} else {phi};
+        let phi: f32 = if last <= 90.0 && phi > 90.0 {180.0 - phi} else {phi};
+
+        let (v0, v1, t): (usize, usize, f32) = bracket(&self.vertical, theta);
+        let (h0, h1, s): (usize, usize, f32) = bracket(&self.horizontal, phi);
+        let at = |h: usize, v: usize| -> f32 { self.values[h * self.vertical.len() + v] };
+        let near: f32 = (1.0 - t) * at(h0, v0) + t * at(h0, v1);
+        let far: f32 = (1.0 - t) * at(h1, v0) + t * at(h1, v1);
+        (1.0 - s) * near + s * far
+    }
+}
\ No newline at end of file
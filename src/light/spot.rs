@@ -0,0 +1,98 @@
+use std::f32::consts::PI;
+
+use crate::aabb::Aabb;
+use crate::colour::{self, Colour};
+use crate::geometry::frame::Frame;
+use crate::light::Light;
+use crate::light::profile::Profile;
+use crate::light::tree::LightBounds;
+use crate::vector_3::{Vector3, Point3};
+
+//RESOLUTION OF THE INTEGRAL GIVING THE POWER
+const POWER_ROWS: usize = 256;
+const POWER_COLUMNS: usize = 64;
+
+//intensity ALONG THE AXIS, FADING SMOOTHLY BETWEEN THE FALLOFF AND CUTOFF ANGLES
+//WITH A PROFILE intensity SCALES ITS CANDELAS INSTEAD
+pub struct Spot {
+    //w POINTS ALONG THE SPOT, u IS WHERE THE HORIZONTAL ANGLES OF THE PROFILE START
+    frame: Frame,
+    intensity: Colour,
+    //COSINES OF THE HALF ANGLES
+    cos_falloff: f32,
+    cos_cutoff: f32,
+    profile: Option<Profile>,
+}
+
+impl Spot {
+    //HALF ANGLES IN RADIANS WITH falloff NO WIDER THAN cutoff, HORIZONTAL ANGLE 0 FACES +X, OR +Z FOR SPOTS ALONG X
+    pub fn new(position: Point3, direction: &Vector3, intensity: Colour, falloff: f32, cutoff: f32, profile: Option<Profile>) -> Spot {
+        let w: Vector3 = direction.unit_vector();
+        let reference: Vector3 = if w.x.abs() < 0.9 {V3!(1.0, 0.0, 0.0)} else {V3!(0.0, 0.0, 1.0)};
+        let u: Vector3 = (reference - Vector3::dot(&reference, &w) * w).unit_vector();
+        let frame: Frame = Frame { origin: position, u, v: Vector3::cross(&w, &u), w };
+        Spot { frame, intensity, cos_falloff: falloff.cos(), cos_cutoff: cutoff.cos(), profile }
+    }
+
+    //MULTIPLE OF intensity SENT ALONG A UNIT DIRECTION IN THE FRAME OF THE SPOT
+    fn shape(&self, local: &Vector3) -> f32 {
+        let falloff: f32 = if local.z >= self.cos_falloff {
+            1.0
+        } else if local.z <= self.cos_cutoff {
+            return 0.0;
+        } else {
+            let t: f32 = (local.z - self.cos_cutoff) / (self.cos_falloff - self.cos_cutoff);
+            t * t * (3.0 - 2.0 * t)
+        };
+        match &self.profile {
+            Some(profile) => falloff * profile.value(f32::acos(local.z.clamp(-1.0, 1.0)), f32::atan2(local.y, local.x)),
+            None => falloff,
+        }
+    }
+}
+
+impl Light for Spot {
+    fn illuminate(&self, point: &Point3) -> Option<(Vector3, f32, Colour)> {
+        let offset: Vector3 = self.frame.origin - *point;
+        let distance_squared: f32 = offset.length_squared();
+        if distance_squared == 0.0 {
+            return None;
+        }
+        let distance: f32 = distance_squared.sqrt();
+        let direction: Vector3 = offset / distance;
+        let shape: f32 = self.shape(&self.frame.to_local_vector(&-direction));
+        if shape <= 0.0 {
+            return None;
+        }
+        Some((direction, distance, (shape / distance_squared) * self.intensity))
+    }
+
+    //MIDPOINT RULE OVER THE CAP INSIDE THE CUTOFF
+    fn power(&self, _radius: f32) -> f32 {
+        let theta_max: f32 = f32::acos(self.cos_cutoff.clamp(-1.0, 1.0));
+        let (d_theta, d_phi): (f32, f32) = (theta_max / POWER_ROWS as f32, 2.0 * PI / POWER_COLUMNS as f32);
+        let mut total: f32 = 0.0;
+        for i in 0..POWER_ROWS {
+            let theta: f32 = (i as f32 + 0.5) * d_theta;
+            for j in 0..POWER_COLUMNS {
+                let phi: f32 = (j as f32 + 0.5) * d_phi;
+                let local: Vector3 = V3!(theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos());
+                total += self.shape(&local) * theta.sin() * d_theta * d_phi;
+            }
+        }
+        total * colour::luminance(&self.intensity)
+    }
+
+    //AS BRIGHT AS A POINT LIGHT AT ITS PEAK WITHIN THE FALLOFF, NOTHING OUTSIDE THE CUTOFF
+    fn light_bounds(&self) -> Option<LightBounds> {
+        let position: Point3 = self.frame.origin;
+        Some(LightBounds {
+            bounds: Aabb::new(position, position),
+            power: 4.0 * PI * colour::luminance(&self.intensity) * self.profile.as_ref().map_or(1.0, |profile: &Profile| profile.peak()),
+            axis: self.frame.w,
+            cos_spread: self.cos_falloff,
+            cos_emission: f32::cos(f32::acos(self.cos_cutoff.clamp(-1.0, 1.0)) - f32::acos(self.cos_falloff.clamp(-1.0, 1.0))),
+            two_sided: false,
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,320 @@
+use std::f32::consts::PI;
+use xorshift::Rng;
+
+use crate::aabb::Aabb;
+use crate::ray::Ray;
+use crate::vector_3::{Vector3, Point3};
+
+//SPLITS TRIED ALONG EACH AXIS
+const BINS: usize = 12;
+
+//A LIGHT AND ITS INDEX IN Lights
+pub type BoundedLight = (LightBounds, usize);
+
+//WHERE LIGHTS ARE, HOW BRIGHT, AND WHICH WAY THEY FACE, AFTER CONTE AND KULLA'S LIGHT BVH
+#[derive(Copy, Clone)]
+pub struct LightBounds {
+    pub bounds: Aabb,
+    pub power: f32,
+    //NORMALS LIE WITHIN THE SPREAD OF axis, LIGHT LEAVES THEM AT UP TO THE EMISSION ANGLE, BOTH AS COSINES
+    pub axis: Vector3,
+    pub cos_spread: f32,
+    pub cos_emission: f32,
+    pub two_sided: bool,
+}
+
+//COSINE AND SINE OF THE DIFFERENCE OF TWO ANGLES, NEVER BELOW 0
+fn subtract((cos_a, sin_a): (f32, f32), (cos_b, sin_b): (f32, f32)) -> (f32, f32) {
+    if cos_a > cos_b {(1.0, 0.0)} else {(cos_a * cos_b + sin_a * sin_b, sin_a * cos_b - cos_a * sin_b)}
+}
+
+fn sine(cos: f32) -> f32 {
+    f32::sqrt(f32::max(0.0, 1.0 - cos * cos))
+}
+
+//ANGLE BETWEEN UNIT VECTORS, ACCURATE WHEN THEY ARE NEARLY PARALLEL
+fn angle_between(a: &Vector3, b: &Vector3) -> f32 {
+    if Vector3::dot(a, b) < 0.0 {
+        PI - 2.0 * f32::asin(f32::min(1.0, (*a + *b).length() / 2.0))
+    } else {
+        2.0 * f32::asin(f32::min(1.0, (*b - *a).length() / 2.0))
+    }
+}
+
+//SMALLEST CONE AROUND TWO CONES, GIVEN AS AXES AND COSINES
+fn cone_union((axis_a, cos_a): (Vector3, f32), (axis_b, cos_b): (Vector3, f32)) -> (Vector3, f32) {
+    let (theta_a, theta_b): (f32, f32) = (f32::acos(cos_a.clamp(-1.0, 1.0)), f32::acos(cos_b.clamp(-1.0, 1.0)));
+    let theta_d: f32 = angle_between(&axis_a, &axis_b);
+    if f32::min(theta_d + theta_b, PI) <= theta_a {
+        return (axis_a, cos_a);
+    }
+    if f32::min(theta_d + theta_a, PI) <= theta_b {
+        return (axis_b, cos_b);
+    }
+    let theta_o: f32 = 0.5 * (theta_a + theta_d + theta_b);
+    let normal: Vector3 = Vector3::cross(&axis_a, &axis_b);
+    if theta_o >= PI || normal.length_squared() < 1e-12 {
+        return (axis_a, -1.0);
+    }
+    //TURN axis_a TOWARDS axis_b UNTIL BOTH CONES FIT
+    let (sin, cos): (f32, f32) = (theta_o - theta_a).sin_cos();
+    let axis: Vector3 = cos * axis_a + sin * Vector3::cross(&normal.unit_vector(), &axis_a);
+    (axis.unit_vector(), theta_o.cos())
+}
+
+impl LightBounds {
+    //FLAT SURFACES, EMITTING FROM BOTH SIDES
+    pub fn flat(bounds: Aabb, power: f32, normal: &Vector3) -> LightBounds {
+        LightBounds { bounds, power, axis: normal.unit_vector(), cos_spread: 1.0, cos_emission: 0.0, two_sided: true }
+    }
+
+    //EMITTING IN EVERY DIRECTION
+    pub fn omnidirectional(bounds: Aabb, power: f32) -> LightBounds {
+        LightBounds { bounds, power, axis: V3!(0.0, 0.0, 1.0), cos_spread: -1.0, cos_emission: 0.0, two_sided: false }
+    }
+
+    fn union(a: &LightBounds, b: &LightBounds) -> LightBounds {
+        if a.power <= 0.0 {
+            return *b;
+        }
+        if b.power <= 0.0 {
+            return *a;
+        }
+        let (axis, cos_spread): (Vector3, f32) = cone_union((a.axis, a.cos_spread), (b.axis, b.cos_spread));
+        LightBounds {
+            bounds: Aabb::union(&a.bounds, &b.bounds),
+            power: a.power + b.power,
+            axis,
+            cos_spread,
+            cos_emission: f32::min(a.cos_emission, b.cos_emission),
+            two_sided: a.two_sided || b.two_sided,
+        }
+    }
+
+    //UPPER BOUND ON THE LIGHT REACHING point ON A SURFACE FACING normal, UP TO A CONSTANT
+    //A POINT IN A MEDIUM HAS NO normal, LIGHT FROM ANY DIRECTION REACHES IT ALIKE
+    fn importance(&self, point: &Point3, normal: Option<&Vector3>) -> f32 {
+        let offset: Vector3 = *point - self.bounds.centroid();
+        let radius_squared: f32 = 0.25 * (self.bounds.max - self.bounds.min).length_squared();
+        let distance_squared: f32 = offset.length_squared();
+        let direction: Vector3 = if distance_squared > 0.0 {offset / distance_squared.sqrt()} else {self.axis};
+
+        //ANGLE FROM THE AXIS TO point, LESS THE SPREAD OF THE NORMALS AND THE ANGLE THE BOUNDS SUBTEND
+        let cos_w: f32 = Vector3::dot(&self.axis, &direction);
+        let cos_w: f32 = if self.two_sided {cos_w.abs()} else {cos_w};
+        let cos_b: f32 = if distance_squared < radius_squared {-1.0} else {f32::sqrt(1.0 - radius_squared / distance_squared)};
+        let (cos_x, sin_x): (f32, f32) = subtract((cos_w, sine(cos_w)), (self.cos_spread, sine(self.cos_spread)));
+        let (cos_p, _): (f32, f32) = subtract((cos_x, sin_x), (cos_b, sine(cos_b)));
+        if cos_p < self.cos_emission {
+            return 0.0;
+        }
+
+        let cos_i: f32 = match normal {
+            Some(normal) => {
+                let cos_i: f32 = Vector3::dot(&direction, normal).abs();
+                subtract((cos_i, sine(cos_i)), (cos_b, sine(cos_b))).0
+            }
+            None => 1.0,
+        };
+        f32::max(0.0, self.power * cos_p * cos_i / f32::max(distance_squared, f32::max(radius_squared, 1e-8)))
+    }
+}
+
+//ONE LIGHT PER LEAF, BY ITS INDEX AMONG THE LIGHTS
+pub enum LightTree {
+    Leaf(LightBounds, usize),
+    Branch(LightBounds, Box<LightTree>, Box<LightTree>),
+}
+
+//ORIENTATION TERM OF THE SPLIT COST, THE SOLID ANGLE THE CONES COVER WEIGHTED BY COSINE
+fn orientation(bounds: &LightBounds) -> f32 {
+    let theta_o: f32 = f32::acos(bounds.cos_spread.clamp(-1.0, 1.0));
+    let theta_e: f32 = f32::acos(bounds.cos_emission.clamp(-1.0, 1.0));
+    let theta_w: f32 = f32::min(theta_o + theta_e, PI);
+    let (sin_o, cos_o): (f32, f32) = theta_o.sin_cos();
+    2.0 * PI * (1.0 - cos_o) + PI / 2.0 * (2.0 * theta_w * sin_o - f32::cos(theta_o - 2.0 * theta_w) - 2.0 * theta_o * sin_o + cos_o)
+}
+
+fn cost(bounds: &LightBounds) -> f32 {
+    bounds.power * orientation(bounds) * bounds.bounds.surface_area()
+}
+
+fn build(mut lights: Vec<BoundedLight>) -> LightTree {
+    if lights.len() == 1 {
+        let (bounds, index): BoundedLight = lights.remove(0);
+        return LightTree::Leaf(bounds, index);
+    }
+    let bounds: LightBounds = lights[1..].iter().fold(lights[0].0, |bounds: LightBounds, (b, _)| LightBounds::union(&bounds, b));
+    let centroids: Aabb = lights.iter()
+        .fold(Aabb::new(lights[0].0.bounds.centroid(), lights[0].0.bounds.centroid()),
+            |centroids: Aabb, (b, _)| Aabb::union(&centroids, &Aabb::new(b.bounds.centroid(), b.bounds.centroid())));
+    let extent: Vector3 = centroids.max - centroids.min;
+    let longest: f32 = f32::max(extent.x, f32::max(extent.y, extent.z));
+
+    //CHEAPEST PLANE ON ANY AXIS, LONG AXES ARE FAVOURED SO NODES STAY COMPACT
+    let mut best: Option<(usize, usize, f32)> = None;
+    for axis in 0..3 {
+        if extent[axis] <= 0.0 {
+            continue;
+        }
+        let bin = |b: &LightBounds| -> usize {
+            let offset: f32 = (b.bounds.centroid()[axis] - centroids.min[axis]) / extent[axis];
+            usize::min((offset * BINS as f32) as usize, BINS - 1)
+        };
+        let mut bins: [Option<LightBounds>; BINS] = [None; BINS];
+        for (b, _) in lights.iter() {
+            let slot: &mut Option<LightBounds> = &mut bins[bin(b)];
+            *slot = Some(slot.map_or(*b, |bounds: LightBounds| LightBounds::union(&bounds, b)));
+        }
+        let merge = |bins: &[Option<LightBounds>]| -> Option<LightBounds> {
+            bins.iter().flatten().fold(None, |merged: Option<LightBounds>, b: &LightBounds| Some(merged.map_or(*b, |merged: LightBounds| LightBounds::union(&merged, b))))
+        };
+        for split in 1..BINS {
+            let (Some(left), Some(right)) = (merge(&bins[..split]), merge(&bins[split..])) else { continue };
+            let split_cost: f32 = (longest / extent[axis]) * (cost(&left) + cost(&right));
+            if best.is_none_or(|(_, _, best_cost)| split_cost < best_cost) {
+                best = Some((axis, split, split_cost));
+            }
+        }
+    }
+
+    let (left, right): (Vec<BoundedLight>, Vec<BoundedLight>) = match best {
+        Some((axis, split, _)) => lights.into_iter().partition(|(b, _)| {
+            let offset: f32 = (b.bounds.centroid()[axis] - centroids.min[axis]) / extent[axis];
+            usize::min((offset * BINS as f32) as usize, BINS - 1) < split
+        }),
+        //ALL CENTROIDS COINCIDE
+        None => {
+            let right: Vec<BoundedLight> = lights.split_off(lights.len() / 2);
+            (lights, right)
+        }
+    };
+    LightTree::Branch(bounds, Box::new(build(left)), Box::new(build(right)))
+}
+
+impl LightTree {
+    pub fn new(lights: Vec<BoundedLight>) -> Option<LightTree> {
+        if lights.is_empty() {None} else {Some(build(lights))}
+    }
+
+    fn bounds(&self) -> &LightBounds {
+        match self {
+            LightTree::Leaf(bounds, _) => bounds,
+            LightTree::Branch(bounds, _, _) => bounds,
+        }
+    }
+
+    //CHANCES OF GOING LEFT AND RIGHT, NONE WHEN NEITHER SIDE CAN LIGHT point
+    fn split(left: &LightTree, right: &LightTree, point: &Point3, normal: Option<&Vector3>) -> Option<(f32, f32)> {
+        let (left, right): (f32, f32) = (left.bounds().importance(point, normal), right.bounds().importance(point, normal));
+        if left + right > 0.0 {Some((left / (left + right), right / (left + right)))} else {None}
+    }
+
+    //INDEX OF A LIGHT, PICKED BY ITS IMPORTANCE AT EACH LEVEL, AND THE CHANCE OF PICKING IT
+    pub fn sample(&self, point: &Point3, normal: Option<&Vector3>, rng: &mut xorshift::StdRng) -> Option<(usize, f32)> {
+        let mut node: &LightTree = self;
+        let mut probability: f32 = 1.0;
+        loop {
+            match node {
+                LightTree::Leaf(bounds, index) => {
+                    return if bounds.importance(point, normal) > 0.0 {Some((*index, probability))} else {None};
+                }
+                LightTree::Branch(_, left, right) => {
+                    let (p_left, p_right): (f32, f32) = LightTree::split(left, right, point, normal)?;
+                    if rng.next_f32() < p_left {
+                        node = left;
+                        probability *= p_left;
+                    } else {
+                        node = right;
+                        probability *= p_right;
+                    }
+                }
+            }
+        }
+    }
+
+    //SUM OVER THE LIGHTS OF THE CHANCE OF PICKING EACH TIMES density OF IT, ONLY NODES ray PASSES THROUGH CAN CONTRIBUTE
+    pub fn pdf(&self, ray: &Ray, normal: Option<&Vector3>, density: &dyn Fn(usize) -> f32) -> f32 {
+        let probability: f32 = match self {
+            LightTree::Leaf(bounds, _) if bounds.importance(&ray.origin, normal) <= 0.0 => 0.0,
+            _ => 1.0,
+        };
+        self.visit(ray, normal, probability, density)
+    }
+
+    fn visit(&self, ray: &Ray, normal: Option<&Vector3>, probability: f32, density: &dyn Fn(usize) -> f32) -> f32 {
+        if probability <= 0.0 || !self.bounds().bounds.hit(ray, 0.0, f32::INFINITY) {
+            return 0.0;
+        }
+        match self {
+            LightTree::Leaf(_, index) => probability * density(*index),
+            LightTree::Branch(_, left, right) => match LightTree::split(left, right, &ray.origin, normal) {
+                Some((p_left, p_right)) => {
+                    left.visit(ray, normal, probability * p_left, density) + right.visit(ray, normal, probability * p_right, density)
+                }
+                None => 0.0,
+            }
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rng(seed: usize) -> xorshift::StdRng {
+        xorshift::SeedableRng::from_seed(&[seed, 2, 3, 4][..])
+    }
+
+    //FLAT, OMNIDIRECTIONAL AND ONE SIDED SPOT-LIKE LIGHTS OF VARIED POWER, SCATTERED AROUND THE ORIGIN
+    fn lights(count: usize, rng: &mut xorshift::StdRng) -> Vec<BoundedLight> {
+        (0..count).map(|index: usize| {
+            let center: Point3 = Vector3::random_between(-10.0, 10.0, rng);
+            let size: Vector3 = Vector3::random_between(0.0, 1.0, rng);
+            let bounds: Aabb = Aabb::new(center - size, center + size);
+            let power: f32 = 0.1 + 10.0 * rng.next_f32();
+            let axis: Vector3 = Vector3::random_unit_vector(rng);
+            let light: LightBounds = match index % 3 {
+                0 => LightBounds::flat(bounds, power, &axis),
+                1 => LightBounds::omnidirectional(bounds, power),
+                _ => LightBounds { bounds, power, axis, cos_spread: 1.0, cos_emission: 0.5, two_sided: false },
+            };
+            (light, index)
+        }).collect()
+    }
+
+    #[test]
+    fn pdf_matches_how_often_sampling_picks_each_light() {
+        let mut rng: xorshift::StdRng = rng(1);
+        let count: usize = 24;
+        let lights: Vec<BoundedLight> = lights(count, &mut rng);
+        let centers: Vec<Point3> = lights.iter().map(|(light, _)| light.bounds.centroid()).collect();
+        let tree: LightTree = LightTree::new(lights).unwrap();
+
+        for (point, normal) in [(V3!(0.0, 0.0, 0.0), None), (V3!(3.0, -2.0, 1.0), Some(V3!(0.0, 1.0, 0.0))), (V3!(15.0, 0.0, 0.0), None)] {
+            let normal: Option<&Vector3> = normal.as_ref();
+            let samples: usize = 100_000;
+            let mut picks: Vec<usize> = vec![0; count];
+            for _ in 0..samples {
+                if let Some((index, probability)) = tree.sample(&point, normal, &mut rng) {
+                    picks[index] += 1;
+                    //THE CHANCE sample REPORTS IS THE ONE pdf GIVES FOR THE SAME LIGHT
+                    let ray: Ray = Ray { origin: point, direction: centers[index] - point, time: 0.0, differentials: None };
+                    let pdf: f32 = tree.pdf(&ray, normal, &|light: usize| if light == index {1.0} else {0.0});
+                    assert!((pdf - probability).abs() <= 1e-5 * probability, "light {} sampled with {} but pdf {}", index, probability, pdf);
+                }
+            }
+
+            let mut total: f32 = 0.0;
+            for (index, center) in centers.iter().enumerate() {
+                let ray: Ray = Ray { origin: point, direction: *center - point, time: 0.0, differentials: None };
+                let pdf: f32 = tree.pdf(&ray, normal, &|light: usize| if light == index {1.0} else {0.0});
+                let frequency: f32 = picks[index] as f32 / samples as f32;
+                //FOUR STANDARD DEVIATIONS OF THE COUNT
+                let tolerance: f32 = 4.0 * f32::sqrt(pdf * (1.0 - pdf) / samples as f32) + 1e-4;
+                assert!((frequency - pdf).abs() <= tolerance, "light {} picked {} of the time but pdf {}", index, frequency, pdf);
+                total += pdf;
+            }
+            assert!(total <= 1.0 + 1e-4 && total > 0.5, "pdfs sum to {}", total);
+        }
+    }
+}
@@ -6,17 +6,33 @@ macro_rules! V3 {
 }
 
 mod vector_3;
+mod matrix_4;
+mod transform;
 mod ray;
+mod aabb;
+mod bvh;
 mod geometry;
 mod hit;
 mod camera;
 mod colour;
 mod material;
+mod texture;
 mod scene;
+mod render;
+mod import;
+mod cli;
+mod image;
+mod light;
+mod medium;
+mod integrator;
+mod background;
+mod distribution;
 
 use geometry::sphere::Sphere;
-use hit::{Surface, HitResult};
 use xorshift::Rng;
+use clap::Parser;
+use std::io::{self, Write};
+use std::fs::File;
 
 use material::lambertian::Lambertian;
 use crate::material::metallic::Metallic;
@@ -26,77 +42,81 @@ use crate::material::Material;
 
 use crate::vector_3::{Vector3};
 use crate::colour::Colour;
-use crate::ray::Ray;
 
-fn ray_colour(ray: &Ray, world: &dyn Surface, depth: u16, rng: &mut xorshift::StdRng) -> Colour {
+fn write_image(arguments: &cli::Arguments, framebuffer: &render::Framebuffer) -> io::Result<()> {
+    let mut out: io::BufWriter<Box<dyn Write>> = io::BufWriter::new(if arguments.output.as_os_str() == "-" {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(File::create(&arguments.output)?)
+    });
 
-    let hit_result: HitResult = world.hit(ray, 0.005, 1024.0);
-    match hit_result {
-        hit::HitResult::Hit(_, _, _, ref material, _) => {
-            if depth > 0 {
-                match material.scatter(ray, &hit_result, rng) {
-                    material::Scatter::None => Colour::ZERO,
-                    material::Scatter::Scatter(attenuation, scatter_ray) => {
-                        attenuation * ray_colour(&scatter_ray, world, depth - 1, rng)
-                    }
-                }
-            } else {
-                Colour::ZERO
-            }
-        },
-        hit::HitResult::None => {
-            let unit_direction: Vector3 = ray.direction.unit_vector();
-            let distance: f32 = 0.5 * (unit_direction.y + 1.0);
-            (1.0 - distance) * Vector3::ONE + distance * V3!(0.5, 0.7, 1.0)
-        }
+    let format: cli::OutputFormat = arguments.format.unwrap_or(cli::OutputFormat::from_extension(&arguments.output));
+    match format {
+        cli::OutputFormat::Ppm => image::ppm::write_ppm(&mut out, framebuffer, arguments.transfer)?,
+        cli::OutputFormat::PpmAscii => image::ppm::write_ppm_ascii(&mut out, framebuffer, arguments.transfer)?,
+        cli::OutputFormat::Png => image::png::write_png(&mut out, framebuffer, arguments.transfer, image::png::BitDepth::Eight)?,
+        cli::OutputFormat::Png16 => image::png::write_png(&mut out, framebuffer, arguments.transfer, image::png::BitDepth::Sixteen)?,
+        cli::OutputFormat::Pfm => image::pfm::write_pfm(&mut out, framebuffer)?,
+        cli::OutputFormat::Hdr => image::hdr::write_hdr(&mut out, framebuffer)?,
+        cli::OutputFormat::Exr => image::exr::write_exr(&mut out, framebuffer, image::exr::PixelType::Half)?,
+        cli::OutputFormat::Exr32 => image::exr::write_exr(&mut out, framebuffer, image::exr::PixelType::Float)?,
     }
+    out.flush()
 }
 
 fn main() {
 
+    let arguments: cli::Arguments = cli::Arguments::parse();
+
     //RANDOM
-    let seed: &[_] = &[1, 2, 3, 4];
+    let seed: &[usize] = &arguments.seed;
     let mut rng: xorshift::StdRng = xorshift::SeedableRng::from_seed(seed);
 
-    //IMAGE
-    const ASPECT_RATIO: f32 = 3.0 / 2.0;
-    const WIDTH : u16 = 1200;
-    const HEIGHT: u16 = (WIDTH as f32 / ASPECT_RATIO) as u16;
-    const SAMPLES: u16 = 64;
-    const DEPTH: u16 = 16;
-
-    //WORLD
-    let world: hit::HitList = scene::random_spheres::random_spheres_scene(&mut rng);
-
-    //CAMERA
-    let origin: Vector3 = V3!(13.0, 2.0, 3.0);
-    let target: Vector3 = V3!(0.0, 0.0, 0.0);
-    let camera: camera::Camera = camera::Camera::new(
-        origin, 
-        target, 
-        Vector3::UP, 
-        20.0,
-        ASPECT_RATIO,
-        0.1,
-        10.0,
-        );
+    //SCENE
+    let mut scene: scene::Scene = match &arguments.scene_file {
+        Some(path) => scene::description::load_scene(path).unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }),
+        None => match arguments.scene {
+            cli::BuiltinScene::RandomSpheres => scene::random_spheres::random_spheres_scene(&mut rng),
+        },
+    };
+    scene.resize(arguments.width, arguments.height);
+    scene.settings.samples = arguments.samples.unwrap_or(scene.settings.samples);
+    scene.settings.depth = arguments.depth.unwrap_or(scene.settings.depth);
 
-    println!("P3\n{} {}", WIDTH, HEIGHT);
-    println!("255");
+    let settings: &scene::Settings = &scene.settings;
+    let camera: &dyn camera::Camera = &*scene.camera;
+    let world: bvh::BvhNode = bvh::BvhNode::new(scene.world);
+    let integrator: integrator::Integrator = integrator::Integrator {
+        world: &world,
+        lights: &scene.lights,
+        background: &*scene.background,
+        medium: scene.medium.as_ref(),
+        depth: settings.depth,
+    };
 
-    for j in (0..HEIGHT).rev() {
-        eprintln!("scanlines remaining: {}", j);
-        for i in 0..WIDTH {
-            let mut pixel_colour: Colour = Colour::ZERO;
-            for _ in 0..SAMPLES {
-                let u: f32 = (i as f32 + rng.next_f32()) / (WIDTH - 1) as f32;
-                let v: f32 = (j as f32 + rng.next_f32()) / (HEIGHT- 1) as f32;
-                let ray: Ray = camera.get_ray(u, v, &mut rng);
-                pixel_colour += ray_colour(&ray, &world, DEPTH, &mut rng);
+    //RENDER
+    let threads: usize = arguments.threads.map_or(std::thread::available_parallelism().map_or(1, |n| n.get()), |n| n as usize);
+    let step: (f32, f32) = (1.0 / (settings.width - 1) as f32, 1.0 / (settings.height - 1) as f32);
+    let framebuffer: render::Framebuffer = render::render(settings.width, settings.height, threads, seed, !arguments.quiet, |i: u16, j: u16, rng: &mut xorshift::StdRng| {
+        let mut pixel_colour: Colour = Colour::ZERO;
+        for _ in 0..settings.samples {
+            let u: f32 = (i as f32 + rng.next_f32()) / (settings.width - 1) as f32;
+            let v: f32 = (j as f32 + rng.next_f32()) / (settings.height - 1) as f32;
+            if let Some(ray) = camera.get_ray(u, v, step, rng) {
+                pixel_colour += integrator.ray_colour(&ray, rng);
             }
-            pixel_colour = pixel_colour / SAMPLES as f32;
-            colour::write_colour(&pixel_colour);
         }
+        pixel_colour / settings.samples as f32
+    });
+
+    if let Err(error) = write_image(&arguments, &framebuffer) {
+        eprintln!("{}: {}", arguments.output.display(), error);
+        std::process::exit(1);
+    }
+    if !arguments.quiet {
+        eprintln!("done.");
     }
-    eprintln!("done.");
 }
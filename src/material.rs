@@ -1,16 +1,53 @@
 use crate::hit;
 use crate::colour;
 use crate::ray;
+use crate::vector_3::Vector3;
 
 pub mod lambertian;
 pub mod metallic;
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod isotropic;
+pub mod microfacet;
+pub mod conductor;
+pub mod principled;
 
 pub enum Scatter {
     None,
-    Scatter(colour::Colour, ray::Ray)
+    //DELTA LOBES, THEY CANNOT BE EVALUATED FOR AN ARBITRARY DIRECTION
+    Specular(colour::Colour, ray::Ray),
+    //ATTENUATION IS BSDF * COSINE / PDF, PDF IS IN SOLID ANGLE
+    Sampled(colour::Colour, ray::Ray, f32),
 }
 
-pub trait Material {
-    fn scatter(&self, ray : &ray::Ray, hit_result: &hit::HitResult, rng: &mut xorshift::StdRng) -> Scatter;
+pub trait Material: Send + Sync {
+    fn scatter(&self, ray : &ray::Ray, hit: &hit::Hit, rng: &mut xorshift::StdRng) -> Scatter;
+
+    fn emitted(&self, _hit: &hit::Hit) -> colour::Colour {
+        colour::Colour::ZERO
+    }
+
+    fn emissive(&self) -> bool {
+        false
+    }
+
+    //PHASE FUNCTIONS SCATTER INSIDE A MEDIUM, WHERE THERE IS NO SURFACE AND THE NORMAL OF THE HIT MEANS NOTHING
+    fn volumetric(&self) -> bool {
+        false
+    }
+
+    //RADIANCE LEAVING AN EMISSIVE SURFACE, FOR WEIGHING LIGHTS BY THEIR POWER
+    fn emission(&self) -> colour::Colour {
+        colour::Colour::ZERO
+    }
+
+    //BSDF TIMES COSINE FOR LIGHT ARRIVING FROM direction
+    fn eval(&self, _ray: &ray::Ray, _hit: &hit::Hit, _direction: &Vector3) -> colour::Colour {
+        colour::Colour::ZERO
+    }
+
+    //SOLID ANGLE DENSITY OF scatter PRODUCING direction
+    fn pdf(&self, _ray: &ray::Ray, _hit: &hit::Hit, _direction: &Vector3) -> f32 {
+        0.0
+    }
 }
\ No newline at end of file
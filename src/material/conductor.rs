@@ -0,0 +1,114 @@
+use xorshift::Rng;
+
+use crate::colour::Colour;
+use crate::geometry::frame::Frame;
+use crate::hit::Hit;
+use crate::material::{self, Scatter};
+use crate::material::microfacet::{self, Ggx};
+use crate::ray::Ray;
+use crate::vector_3::Vector3;
+
+#[derive(Copy, Clone)]
+pub enum Metal {
+    Gold,
+    Silver,
+    Copper,
+    Aluminium,
+    Iron,
+    Chromium,
+}
+
+impl Metal {
+    //REAL AND IMAGINARY PARTS OF THE INDEX OF REFRACTION AT RED, GREEN AND BLUE WAVELENGTHS
+    pub fn ior(&self) -> (Colour, Colour) {
+        match self {
+            Metal::Gold => (V3!(0.143, 0.374, 1.442), V3!(3.983, 2.385, 1.603)),
+            Metal::Silver => (V3!(0.155, 0.117, 0.138), V3!(4.828, 3.122, 2.147)),
+            Metal::Copper => (V3!(0.200, 0.924, 1.102), V3!(3.912, 2.452, 2.142)),
+            Metal::Aluminium => (V3!(1.657, 0.880, 0.521), V3!(9.224, 6.270, 4.837)),
+            Metal::Iron => (V3!(2.911, 2.950, 2.585), V3!(3.089, 2.932, 2.767)),
+            Metal::Chromium => (V3!(3.105, 3.182, 2.223), V3!(3.324, 3.332, 3.117)),
+        }
+    }
+}
+
+//MICROFACET METAL WITH THE FULL FRESNEL TERM OF A COMPLEX INDEX OF REFRACTION eta + i k
+pub struct Conductor {
+    pub eta: Colour,
+    pub k: Colour,
+    pub distribution: Ggx,
+}
+
+//UNPOLARISED REFLECTANCE OF ONE CHANNEL
+fn fresnel_channel(cos: f32, eta: f32, k: f32) -> f32 {
+    let cos_2: f32 = cos * cos;
+    let sin_2: f32 = 1.0 - cos_2;
+    let t0: f32 = eta * eta - k * k - sin_2;
+    let a2_plus_b2: f32 = f32::sqrt(t0 * t0 + 4.0 * eta * eta * k * k);
+    let a: f32 = f32::sqrt(f32::max(0.0, 0.5 * (a2_plus_b2 + t0)));
+    let t1: f32 = a2_plus_b2 + cos_2;
+    let t2: f32 = 2.0 * cos * a;
+    let perpendicular: f32 = (t1 - t2) / (t1 + t2);
+    let t3: f32 = cos_2 * a2_plus_b2 + sin_2 * sin_2;
+    let t4: f32 = t2 * sin_2;
+    let parallel: f32 = perpendicular * (t3 - t4) / (t3 + t4);
+    0.5 * (parallel + perpendicular)
+}
+
+impl Conductor {
+    fn fresnel(&self, cos: f32) -> Colour {
+        let cos: f32 = cos.clamp(0.0, 1.0);
+        V3!(fresnel_channel(cos, self.eta.x, self.k.x), fresnel_channel(cos, self.eta.y, self.k.y), fresnel_channel(cos, self.eta.z, self.k.z))
+    }
+}
+
+//A CONDUCTOR ONLY REFLECTS, SO BOTH DIRECTIONS MUST BE ABOVE THE SURFACE
+fn reflected(ray: &Ray, hit: &Hit, direction: &Vector3) -> Option<(Vector3, Vector3)> {
+    microfacet::directions(ray, hit, direction).filter(|(_, wi): &(Vector3, Vector3)| wi.z > 0.0)
+}
+
+impl material::Material for Conductor {
+
+    fn scatter(&self, ray: &Ray, hit: &Hit, rng: &mut xorshift::StdRng) -> Scatter {
+        let frame: Frame = microfacet::frame(hit);
+        let wo: Vector3 = frame.to_local_vector(&-ray.direction.unit_vector());
+        if wo.z <= 0.0 {
+            return Scatter::None;
+        }
+        if self.distribution.is_smooth() {
+            let direction: Vector3 = frame.to_world_vector(&V3!(-wo.x, -wo.y, wo.z));
+            return Scatter::Specular(self.fresnel(wo.z), Ray { origin: hit.point, direction, time: ray.time, differentials: None });
+        }
+
+        let m: Vector3 = self.distribution.sample_visible(&wo, rng.next_f32(), rng.next_f32());
+        let wi: Vector3 = microfacet::reflect(&wo, &m);
+        let direction: Vector3 = frame.to_world_vector(&wi);
+        if wi.z <= 0.0 || Vector3::dot(&hit.geometric_normal, &direction) <= 0.0 {
+            return Scatter::None;
+        }
+
+        //BSDF * COSINE / PDF REDUCES TO F * G2 / G1 FOR VISIBLE NORMAL SAMPLING
+        let pdf: f32 = self.distribution.pdf_visible(&wo, &m) / (4.0 * Vector3::dot(&wo, &m));
+        let weight: f32 = self.distribution.g2(&wo, &wi) / self.distribution.g1(&wo);
+        Scatter::Sampled(self.fresnel(Vector3::dot(&wo, &m)) * weight, Ray { origin: hit.point, direction, time: ray.time, differentials: None }, pdf)
+    }
+
+    fn eval(&self, ray: &Ray, hit: &Hit, direction: &Vector3) -> Colour {
+        if self.distribution.is_smooth() {
+            return Colour::ZERO;
+        }
+        let Some((wo, wi)) = reflected(ray, hit, direction) else { return Colour::ZERO };
+        let m: Vector3 = (wo + wi).unit_vector();
+        let specular: f32 = self.distribution.d(&m) * self.distribution.g2(&wo, &wi) / (4.0 * wo.z);
+        self.fresnel(Vector3::dot(&wi, &m)) * specular
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &Hit, direction: &Vector3) -> f32 {
+        if self.distribution.is_smooth() {
+            return 0.0;
+        }
+        let Some((wo, wi)) = reflected(ray, hit, direction) else { return 0.0 };
+        let m: Vector3 = (wo + wi).unit_vector();
+        self.distribution.pdf_visible(&wo, &m) / (4.0 * Vector3::dot(&wo, &m))
+    }
+}
\ No newline at end of file
@@ -1,12 +1,18 @@
 use xorshift::Rng;
 
-use crate::material;
+use crate::colour::Colour;
+use crate::geometry::frame::Frame;
+use crate::material::{self, Scatter};
+use crate::material::microfacet::{self, Ggx};
 use crate::vector_3::Vector3;
 use crate::ray::Ray;
-use crate::hit::HitResult;
+use crate::hit::Hit;
 
 pub struct Dielectric {
-    pub refraction_index: f32
+    pub refraction_index: f32,
+    pub distribution: Ggx,
+    //BEER-LAMBERT COEFFICIENT PER UNIT OF DISTANCE TRAVELLED INSIDE, ZERO FOR CLEAR GLASS
+    pub absorption: Colour,
 }
 
 fn reflectance(cos: f32, refraction_index: f32) -> f32 {
@@ -15,27 +21,122 @@ fn reflectance(cos: f32, refraction_index: f32) -> f32 {
     r0 + (1.0 - r0) * (1.0 - cos).powi(5)
 }
 
+//UNPOLARISED REFLECTANCE, eta IS THE INDEX ON THE FAR SIDE OVER THE ONE ON THE INCIDENT SIDE
+pub fn fresnel(cos: f32, eta: f32) -> f32 {
+    let cos_i: f32 = cos.clamp(0.0, 1.0);
+    let sin_t_2: f32 = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin_t_2 >= 1.0 {
+        return 1.0;
+    }
+    let cos_t: f32 = f32::sqrt(1.0 - sin_t_2);
+    let perpendicular: f32 = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    let parallel: f32 = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    0.5 * (perpendicular * perpendicular + parallel * parallel)
+}
+
+//BSDF * COSINE AND PDF OF A ROUGH INTERFACE IN THE SHADING FRAME, wi BELOW THE SURFACE IS A TRANSMISSION
+pub fn lobe(distribution: &Ggx, wo: &Vector3, wi: &Vector3, eta: f32) -> (f32, f32) {
+    let reflected: bool = wi.z > 0.0;
+    let m: Vector3 = if reflected {*wo + *wi} else {*wo + eta * *wi}.unit_vector();
+    let m: Vector3 = if m.z < 0.0 {-m} else {m};
+    let (cos_o, cos_i): (f32, f32) = (Vector3::dot(wo, &m), Vector3::dot(wi, &m));
+    if cos_o <= 0.0 || (cos_i > 0.0) != reflected {
+        return (0.0, 0.0);
+    }
+    let f: f32 = fresnel(cos_o, eta);
+    let d: f32 = distribution.d(&m);
+    let visible: f32 = distribution.pdf_visible(wo, &m);
+    if reflected {
+        (f * d * distribution.g2(wo, wi) / (4.0 * wo.z), f * visible / (4.0 * cos_o))
+    } else {
+        //NO 1 / eta^2 RADIANCE SCALING, LIKE THE SMOOTH CASE, IT CANCELS ON PATHS THAT ENTER AND LEAVE
+        let jacobian: f32 = eta * eta * -cos_i / ((cos_o + eta * cos_i) * (cos_o + eta * cos_i));
+        ((1.0 - f) * d * distribution.g2(wo, wi) * cos_o * jacobian / wo.z, (1.0 - f) * visible * jacobian)
+    }
+}
+
+impl Dielectric {
+    //SMOOTH AND CLEAR
+    pub fn new(refraction_index: f32) -> Dielectric {
+        Dielectric { refraction_index, distribution: Ggx::from_roughness((0.0, 0.0)), absorption: Colour::ZERO }
+    }
+
+    //A HIT FROM INSIDE MEANS THE RAY CROSSED THE MEDIUM TO GET THERE
+    fn transmittance(&self, ray: &Ray, hit: &Hit) -> Colour {
+        if hit.outward {
+            return Colour::ONE;
+        }
+        let distance: f32 = hit.distance * ray.direction.length();
+        V3!(f32::exp(-self.absorption.x * distance), f32::exp(-self.absorption.y * distance), f32::exp(-self.absorption.z * distance))
+    }
+
+    //INDEX BEYOND THE SURFACE OVER THE INDEX ON THE SIDE OF THE RAY
+    fn eta(&self, hit: &Hit) -> f32 {
+        if hit.outward {self.refraction_index} else {1.0 / self.refraction_index}
+    }
+
+}
+
 impl material::Material for Dielectric {
 
-    fn scatter(&self, ray: &Ray, hit_result: &HitResult, rng: &mut xorshift::StdRng) -> material::Scatter {
-        match hit_result {
-            HitResult::Hit(position, normal, _, _, outward) => {
-                let refraction_ratio: f32 = if *outward {1.0 / self.refraction_index} else {self.refraction_index};
-                let unit_direction: Vector3 = Vector3::unit_vector(&ray.direction);
-
-                let cos_theta: f32 = f32::min(Vector3::dot(&-unit_direction, normal), 1.0);
-                let sin_theta: f32 = f32::sqrt(1.0 - cos_theta * cos_theta);
-    
-                let cannot_refract: bool = refraction_ratio * sin_theta > 1.0;
-                let direction: Vector3 = if cannot_refract || reflectance(cos_theta, refraction_ratio) > rng.next_f32()
-                                            {Vector3::reflect(&unit_direction, normal)} 
-                                        else 
-                                            {Vector3::refract(&unit_direction, normal, refraction_ratio)};
-                material::Scatter::Scatter(Vector3::ONE, Ray { origin: *position, direction: direction })
-            }
-            HitResult::None => {
-                material::Scatter::None
+    fn scatter(&self, ray: &Ray, hit: &Hit, rng: &mut xorshift::StdRng) -> Scatter {
+        if self.distribution.is_smooth() {
+            let normal: &Vector3 = &hit.normal;
+            let refraction_ratio: f32 = if hit.outward {1.0 / self.refraction_index} else {self.refraction_index};
+            let unit_direction: Vector3 = Vector3::unit_vector(&ray.direction);
+
+            let cos_theta: f32 = f32::min(Vector3::dot(&-unit_direction, normal), 1.0);
+            let sin_theta: f32 = f32::sqrt(1.0 - cos_theta * cos_theta);
+
+            let cannot_refract: bool = refraction_ratio * sin_theta > 1.0;
+            let direction: Vector3 = if cannot_refract || reflectance(cos_theta, refraction_ratio) > rng.next_f32()
+                                        {Vector3::reflect(&unit_direction, normal)}
+                                    else
+                                        {Vector3::refract(&unit_direction, normal, refraction_ratio)};
+            return Scatter::Specular(self.transmittance(ray, hit), Ray { origin: hit.point, direction, time: ray.time, differentials: None });
+        }
+
+        let frame: Frame = microfacet::frame(hit);
+        let wo: Vector3 = frame.to_local_vector(&-ray.direction.unit_vector());
+        if wo.z <= 0.0 {
+            return Scatter::None;
+        }
+        let eta: f32 = self.eta(hit);
+        let m: Vector3 = self.distribution.sample_visible(&wo, rng.next_f32(), rng.next_f32());
+        let f: f32 = fresnel(Vector3::dot(&wo, &m), eta);
+        let wi: Vector3 = if rng.next_f32() < f {
+            microfacet::reflect(&wo, &m)
+        } else {
+            match microfacet::refract(&wo, &m, eta) {
+                Some(wi) => wi,
+                None => return Scatter::None,
             }
+        };
+        let direction: Vector3 = frame.to_world_vector(&wi);
+        let Some((wo, wi)) = microfacet::directions(ray, hit, &direction) else { return Scatter::None };
+
+        //BSDF * COSINE / PDF REDUCES TO G2 / G1 FOR BOTH LOBES, THE FRESNEL TERM IS SPENT ON CHOOSING ONE
+        let (_, pdf): (f32, f32) = lobe(&self.distribution, &wo, &wi, eta);
+        if pdf <= 0.0 {
+            return Scatter::None;
+        }
+        let weight: f32 = self.distribution.g2(&wo, &wi) / self.distribution.g1(&wo);
+        Scatter::Sampled(weight * self.transmittance(ray, hit), Ray { origin: hit.point, direction, time: ray.time, differentials: None }, pdf)
+    }
+
+    fn eval(&self, ray: &Ray, hit: &Hit, direction: &Vector3) -> Colour {
+        if self.distribution.is_smooth() {
+            return Colour::ZERO;
+        }
+        let Some((wo, wi)) = microfacet::directions(ray, hit, direction) else { return Colour::ZERO };
+        lobe(&self.distribution, &wo, &wi, self.eta(hit)).0 * self.transmittance(ray, hit)
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &Hit, direction: &Vector3) -> f32 {
+        if self.distribution.is_smooth() {
+            return 0.0;
         }
+        let Some((wo, wi)) = microfacet::directions(ray, hit, direction) else { return 0.0 };
+        lobe(&self.distribution, &wo, &wi, self.eta(hit)).1
     }
 }
\ No newline at end of file
@@ -0,0 +1,27 @@
+use crate::colour::Colour;
+use crate::material;
+use crate::ray::Ray;
+use crate::hit::Hit;
+
+pub struct DiffuseLight {
+    pub emit: Colour
+}
+
+impl material::Material for DiffuseLight {
+
+    fn scatter(&self, _: &Ray, _: &Hit, _: &mut xorshift::StdRng) -> material::Scatter {
+        material::Scatter::None
+    }
+
+    fn emitted(&self, _: &Hit) -> Colour {
+        self.emit
+    }
+
+    fn emissive(&self) -> bool {
+        true
+    }
+
+    fn emission(&self) -> Colour {
+        self.emit
+    }
+}
\ No newline at end of file
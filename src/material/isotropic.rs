@@ -0,0 +1,34 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::colour::Colour;
+use crate::material;
+use crate::vector_3::Vector3;
+use crate::ray::Ray;
+use crate::hit::Hit;
+use crate::texture::{self, Texture};
+
+//PHASE FUNCTION SCATTERING EQUALLY IN EVERY DIRECTION, THERE IS NO COSINE INSIDE A MEDIUM
+pub struct Isotropic {
+    pub albedo: Arc<dyn Texture>
+}
+
+impl material::Material for Isotropic {
+
+    fn scatter(&self, ray: &Ray, hit: &Hit, rng: &mut xorshift::StdRng) -> material::Scatter {
+        let direction: Vector3 = Vector3::random_unit_vector(rng);
+        material::Scatter::Sampled(texture::at(&*self.albedo, hit), Ray { origin: hit.point, direction, time: ray.time, differentials: None }, 1.0 / (4.0 * PI))
+    }
+
+    fn volumetric(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, _: &Ray, hit: &Hit, _: &Vector3) -> Colour {
+        texture::at(&*self.albedo, hit) / (4.0 * PI)
+    }
+
+    fn pdf(&self, _: &Ray, _: &Hit, _: &Vector3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+}
\ No newline at end of file
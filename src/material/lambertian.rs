@@ -1,28 +1,45 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
 use crate::colour::Colour;
 use crate::material;
 use crate::vector_3::Vector3;
 use crate::ray::Ray;
-use crate::hit::HitResult;
+use crate::hit::Hit;
+use crate::texture::{self, Texture};
 
 pub struct Lambertian {
-    pub albedo: Colour
+    pub albedo: Arc<dyn Texture>
+}
+
+//AGAINST THE SHADING NORMAL, ZERO BELOW THE TRUE SURFACE SO SMOOTHED MESHES DO NOT LEAK LIGHT
+fn cosine(hit: &Hit, direction: &Vector3) -> f32 {
+    if Vector3::dot(&hit.geometric_normal, direction) <= 0.0 {
+        return 0.0;
+    }
+    f32::max(0.0, Vector3::dot(&hit.normal, &direction.unit_vector()))
 }
 
 impl material::Material for Lambertian {
 
-    fn scatter(&self, _: &Ray, hit_result: &HitResult, rng: &mut xorshift::StdRng) -> material::Scatter {
-        match hit_result {
-            HitResult::Hit(position, normal, _, _, _) => {
-                let mut scatter_direction: Vector3 = *normal + Vector3::random_unit_vector(rng);
-                if scatter_direction.length_squared() < 0.001 {
-                    scatter_direction = *normal;
-                }
-                
-                material::Scatter::Scatter(self.albedo, Ray { origin: *position, direction: scatter_direction })
-            }
-            HitResult::None => {
-                return material::Scatter::None;
-            }
+    fn scatter(&self, ray: &Ray, hit: &Hit, rng: &mut xorshift::StdRng) -> material::Scatter {
+        let mut scatter_direction: Vector3 = hit.normal + Vector3::random_unit_vector(rng);
+        if scatter_direction.length_squared() < 0.001 {
+            scatter_direction = hit.normal;
+        }
+
+        let pdf: f32 = cosine(hit, &scatter_direction) / PI;
+        if pdf <= 0.0 {
+            return material::Scatter::None;
         }
+        material::Scatter::Sampled(texture::at(&*self.albedo, hit), Ray { origin: hit.point, direction: scatter_direction, time: ray.time, differentials: None }, pdf)
+    }
+
+    fn eval(&self, _: &Ray, hit: &Hit, direction: &Vector3) -> Colour {
+        texture::at(&*self.albedo, hit) * (cosine(hit, direction) / PI)
+    }
+
+    fn pdf(&self, _: &Ray, hit: &Hit, direction: &Vector3) -> f32 {
+        cosine(hit, direction) / PI
     }
 }
\ No newline at end of file
@@ -1,25 +1,22 @@
-use crate::colour::Colour;
+use std::sync::Arc;
+
 use crate::material;
 use crate::vector_3::Vector3;
 use crate::ray::Ray;
-use crate::hit::HitResult;
+use crate::hit::Hit;
+use crate::texture::{self, Texture};
 
 use super::Scatter;
 
 pub struct Metallic {
-    pub albedo: Colour,
+    pub albedo: Arc<dyn Texture>,
     pub fuzziness: f32
 }
 
 impl material::Material for Metallic {
 
-    fn scatter(&self, ray: &Ray, hit_result: &HitResult, rng: &mut xorshift::StdRng) -> material::Scatter {
-        match hit_result {
-            HitResult::Hit(position, normal, _, _, _) => {
-                let reflected: Vector3 = Vector3::reflect(&ray.direction.unit_vector(), normal);
-                Scatter::Scatter(self.albedo, Ray{ origin: *position, direction: reflected + self.fuzziness * Vector3::random_in_sphere(rng)})
-            }
-            HitResult::None => material::Scatter::None
-        }
+    fn scatter(&self, ray: &Ray, hit: &Hit, rng: &mut xorshift::StdRng) -> material::Scatter {
+        let reflected: Vector3 = Vector3::reflect(&ray.direction.unit_vector(), &hit.normal);
+        Scatter::Specular(texture::at(&*self.albedo, hit), Ray{ origin: hit.point, direction: reflected + self.fuzziness * Vector3::random_in_sphere(rng), time: ray.time, differentials: None })
     }
 }
\ No newline at end of file
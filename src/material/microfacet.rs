@@ -0,0 +1,109 @@
+use std::f32::consts::PI;
+
+use crate::geometry::frame::Frame;
+use crate::hit::Hit;
+use crate::ray::Ray;
+use crate::vector_3::Vector3;
+
+//BELOW THIS ALPHA A SURFACE IS TREATED AS A PERFECT MIRROR
+const SMOOTH: f32 = 1e-3;
+
+//ANISOTROPIC TROWBRIDGE-REITZ (GGX) DISTRIBUTION OF NORMALS, IN THE SHADING FRAME WITH X ALONG THE TANGENT
+#[derive(Copy, Clone)]
+pub struct Ggx {
+    pub alpha: (f32, f32),
+}
+
+//SHADING FRAME OF A HIT, Z IS THE SHADING NORMAL
+pub fn frame(hit: &Hit) -> Frame {
+    Frame { origin: hit.point, u: hit.tangent, v: Vector3::cross(&hit.normal, &hit.tangent), w: hit.normal }
+}
+
+//OUTGOING AND INCOMING DIRECTIONS IN THE SHADING FRAME, NONE WHEN wo IS BELOW THE SHADING NORMAL
+//OR THE SHADING AND GEOMETRIC NORMALS DISAGREE ON WHICH SIDE direction LEAVES FROM
+pub fn directions(ray: &Ray, hit: &Hit, direction: &Vector3) -> Option<(Vector3, Vector3)> {
+    let frame: Frame = frame(hit);
+    let wo: Vector3 = frame.to_local_vector(&-ray.direction.unit_vector());
+    let wi: Vector3 = frame.to_local_vector(&direction.unit_vector());
+    let consistent: bool = wo.z > 0.0 && wi.z != 0.0 && (wi.z > 0.0) == (Vector3::dot(&hit.geometric_normal, direction) > 0.0);
+    if consistent {Some((wo, wi))} else {None}
+}
+
+pub fn reflect(w: &Vector3, m: &Vector3) -> Vector3 {
+    2.0 * Vector3::dot(w, m) * *m - *w
+}
+
+//eta IS THE INDEX ON THE FAR SIDE OVER THE ONE ON THE SIDE OF w, NONE ON TOTAL INTERNAL REFLECTION
+pub fn refract(w: &Vector3, m: &Vector3, eta: f32) -> Option<Vector3> {
+    let cos_i: f32 = Vector3::dot(w, m);
+    let sin_t_2: f32 = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin_t_2 >= 1.0 {
+        return None;
+    }
+    let cos_t: f32 = f32::sqrt(1.0 - sin_t_2);
+    Some((cos_i / eta - cos_t) * *m - *w / eta)
+}
+
+impl Ggx {
+    //PERCEPTUAL ROUGHNESS ALONG THE TANGENT AND THE BITANGENT, SQUARED SO IT LOOKS LINEAR
+    pub fn from_roughness(roughness: (f32, f32)) -> Ggx {
+        Ggx { alpha: (roughness.0 * roughness.0, roughness.1 * roughness.1) }
+    }
+
+    pub fn is_smooth(&self) -> bool {
+        f32::max(self.alpha.0, self.alpha.1) < SMOOTH
+    }
+
+    pub fn d(&self, m: &Vector3) -> f32 {
+        if m.z <= 0.0 {
+            return 0.0;
+        }
+        let (ax, ay): (f32, f32) = (f32::max(self.alpha.0, SMOOTH), f32::max(self.alpha.1, SMOOTH));
+        let t: f32 = (m.x / ax) * (m.x / ax) + (m.y / ay) * (m.y / ay) + m.z * m.z;
+        1.0 / (PI * ax * ay * t * t)
+    }
+
+    fn lambda(&self, w: &Vector3) -> f32 {
+        if w.z == 0.0 {
+            return f32::INFINITY;
+        }
+        let (ax, ay): (f32, f32) = (f32::max(self.alpha.0, SMOOTH), f32::max(self.alpha.1, SMOOTH));
+        let alpha_tan_2: f32 = ((ax * w.x) * (ax * w.x) + (ay * w.y) * (ay * w.y)) / (w.z * w.z);
+        0.5 * (f32::sqrt(1.0 + alpha_tan_2) - 1.0)
+    }
+
+    //SMITH MASKING OF ONE DIRECTION
+    pub fn g1(&self, w: &Vector3) -> f32 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    //HEIGHT CORRELATED MASKING AND SHADOWING
+    pub fn g2(&self, wo: &Vector3, wi: &Vector3) -> f32 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    //NORMAL SEEN FROM wo, HEITZ 2018, wo MUST BE ABOVE THE SURFACE
+    pub fn sample_visible(&self, wo: &Vector3, u1: f32, u2: f32) -> Vector3 {
+        let (ax, ay): (f32, f32) = (f32::max(self.alpha.0, SMOOTH), f32::max(self.alpha.1, SMOOTH));
+        let vh: Vector3 = V3!(ax * wo.x, ay * wo.y, wo.z).unit_vector();
+        let length_squared: f32 = vh.x * vh.x + vh.y * vh.y;
+        let t1: Vector3 = if length_squared > 0.0 {V3!(-vh.y, vh.x, 0.0) / f32::sqrt(length_squared)} else {Vector3::X};
+        let t2: Vector3 = Vector3::cross(&vh, &t1);
+
+        let r: f32 = f32::sqrt(u1);
+        let phi: f32 = 2.0 * PI * u2;
+        let p1: f32 = r * f32::cos(phi);
+        let s: f32 = 0.5 * (1.0 + vh.z);
+        let p2: f32 = (1.0 - s) * f32::sqrt(1.0 - p1 * p1) + s * r * f32::sin(phi);
+        let nh: Vector3 = p1 * t1 + p2 * t2 + f32::sqrt(f32::max(0.0, 1.0 - p1 * p1 - p2 * p2)) * vh;
+        V3!(ax * nh.x, ay * nh.y, f32::max(1e-6, nh.z)).unit_vector()
+    }
+
+    //DENSITY OF sample_visible PRODUCING m
+    pub fn pdf_visible(&self, wo: &Vector3, m: &Vector3) -> f32 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+        self.g1(wo) * f32::max(0.0, Vector3::dot(wo, m)) * self.d(m) / wo.z
+    }
+}
\ No newline at end of file
@@ -0,0 +1,179 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use xorshift::Rng;
+
+use crate::colour::{self, Colour};
+use crate::geometry::frame::Frame;
+use crate::hit::Hit;
+use crate::material::{self, Scatter};
+use crate::material::dielectric;
+use crate::material::microfacet::{self, Ggx};
+use crate::ray::Ray;
+use crate::texture::{self, Texture};
+use crate::vector_3::Vector3;
+
+//REFLECTANCE OF THE CLEAR COAT AT NORMAL INCIDENCE, AN INDEX OF 1.5
+const COAT: f32 = 0.04;
+
+//DISNEY STYLE UBER-MATERIAL, SCALAR PARAMETERS TAKE THE MEAN OF THEIR TEXTURE'S CHANNELS
+pub struct Principled {
+    pub base_colour: Arc<dyn Texture>,
+    pub metallic: Arc<dyn Texture>,
+    pub roughness: Arc<dyn Texture>,
+    //REFLECTANCE AT NORMAL INCIDENCE OVER 0.08, 0.5 IS AN INDEX OF 1.5
+    pub specular: Arc<dyn Texture>,
+    pub sheen: Arc<dyn Texture>,
+    pub clearcoat: Arc<dyn Texture>,
+    pub clearcoat_roughness: f32,
+    pub transmission: Arc<dyn Texture>,
+}
+
+//THE PARAMETERS AT ONE HIT AND THE CHANCE OF SAMPLING EACH LOBE FOR ITS OUTGOING DIRECTION
+struct Lobes {
+    base: Colour,
+    metallic: f32,
+    roughness: f32,
+    sheen: f32,
+    clearcoat: f32,
+    transmission: f32,
+    distribution: Ggx,
+    coat: Ggx,
+    //INDEX OF THE MATERIAL, AND THE INDEX BEYOND THE SURFACE OVER THE ONE ON THE SIDE OF THE RAY
+    eta: f32,
+    relative: f32,
+    //DIFFUSE, SPECULAR, GLASS AND CLEAR COAT, THEY ADD UP TO ONE
+    probabilities: [f32; 4],
+}
+
+fn scalar(texture: &dyn Texture, hit: &Hit) -> f32 {
+    let value: Colour = texture::at(texture, hit);
+    ((value.x + value.y + value.z) / 3.0).clamp(0.0, 1.0)
+}
+
+fn schlick(f0: f32, cos: f32) -> f32 {
+    f0 + (1.0 - f0) * (1.0 - cos.clamp(0.0, 1.0)).powi(5)
+}
+
+impl Principled {
+    fn lobes(&self, hit: &Hit, wo: &Vector3) -> Lobes {
+        let (metallic, roughness, transmission): (f32, f32, f32) = (scalar(&*self.metallic, hit), scalar(&*self.roughness, hit), scalar(&*self.transmission, hit));
+        let clearcoat: f32 = scalar(&*self.clearcoat, hit);
+        let f0: f32 = f32::max(0.08 * scalar(&*self.specular, hit), 1e-4);
+        let eta: f32 = (1.0 + f0.sqrt()) / (1.0 - f0.sqrt());
+
+        //THE COAT REFLECTS FIRST, THE REST SPLITS BETWEEN METAL, GLASS AND A DIFFUSE BASE UNDER A SPECULAR LAYER
+        let coated: f32 = clearcoat * schlick(COAT, wo.z);
+        let below: f32 = 1.0 - coated;
+        let opaque: f32 = (1.0 - metallic) * (1.0 - transmission);
+        let fresnel: f32 = dielectric::fresnel(wo.z, eta);
+        Lobes {
+            base: texture::at(&*self.base_colour, hit),
+            metallic,
+            roughness,
+            sheen: scalar(&*self.sheen, hit),
+            clearcoat,
+            transmission,
+            distribution: Ggx::from_roughness((roughness, roughness)),
+            coat: Ggx::from_roughness((self.clearcoat_roughness, self.clearcoat_roughness)),
+            eta,
+            relative: if hit.outward {eta} else {1.0 / eta},
+            probabilities: [below * opaque * (1.0 - fresnel), below * (metallic + opaque * fresnel), below * (1.0 - metallic) * transmission, coated],
+        }
+    }
+
+    //BSDF * COSINE AND PDF OF THE WHOLE MIXTURE IN THE SHADING FRAME
+    fn evaluate(&self, lobes: &Lobes, wo: &Vector3, wi: &Vector3) -> (Colour, f32) {
+        let below: f32 = 1.0 - lobes.clearcoat * schlick(COAT, wo.z);
+        let (glass, glass_pdf): (f32, f32) = dielectric::lobe(&lobes.distribution, wo, wi, lobes.relative);
+        let glass_weight: f32 = below * (1.0 - lobes.metallic) * lobes.transmission;
+        let glass_pdf: f32 = lobes.probabilities[2] * glass_pdf;
+
+        //TINTED ON THE WAY IN AND ON THE WAY OUT, SO A THIN OBJECT TAKES THE BASE COLOUR
+        if wi.z <= 0.0 {
+            let tint: Colour = V3!(lobes.base.x.sqrt(), lobes.base.y.sqrt(), lobes.base.z.sqrt());
+            return (glass_weight * glass * tint, glass_pdf);
+        }
+
+        let m: Vector3 = (*wo + *wi).unit_vector();
+        let cos_d: f32 = Vector3::dot(wi, &m);
+        let reflection = |distribution: &Ggx| -> (f32, f32) {
+            (distribution.d(&m) * distribution.g2(wo, wi) / (4.0 * wo.z), distribution.pdf_visible(wo, &m) / (4.0 * Vector3::dot(wo, &m)))
+        };
+
+        //BURLEY DIFFUSE WITH RETRO-REFLECTION, SCALED BY WHAT THE SPECULAR LAYER LETS THROUGH
+        let opaque: f32 = (1.0 - lobes.metallic) * (1.0 - lobes.transmission);
+        let (fl, fv): (f32, f32) = ((1.0 - wi.z).powi(5), (1.0 - wo.z).powi(5));
+        let retro: f32 = 2.0 * lobes.roughness * cos_d * cos_d;
+        let burley: f32 = (1.0 - 0.5 * fl) * (1.0 - 0.5 * fv) + retro * (fl + fv + fl * fv * (retro - 1.0));
+        let tint: Colour = if colour::luminance(&lobes.base) > 0.0 {lobes.base / colour::luminance(&lobes.base)} else {Colour::ONE};
+        let sheen: Colour = lobes.sheen * (1.0 - cos_d).powi(5) * (0.5 * Colour::ONE + 0.5 * tint);
+        let diffuse: Colour = below * opaque * (1.0 - dielectric::fresnel(wo.z, lobes.eta)) * (lobes.base * (burley / PI) + sheen) * wi.z;
+
+        //METALS TAKE THEIR COLOUR FROM THE BASE, EVERYTHING ELSE REFLECTS WHITE
+        let (specular, specular_pdf): (f32, f32) = reflection(&lobes.distribution);
+        let edge: f32 = (1.0 - cos_d).powi(5);
+        let fresnel: Colour = lobes.metallic * (lobes.base + edge * (Colour::ONE - lobes.base)) + opaque * dielectric::fresnel(cos_d, lobes.eta) * Colour::ONE;
+        let specular_value: Colour = below * specular * fresnel;
+
+        let (coat, coat_pdf): (f32, f32) = reflection(&lobes.coat);
+        let coat_value: f32 = lobes.clearcoat * schlick(COAT, cos_d) * coat;
+
+        let value: Colour = diffuse + specular_value + glass_weight * glass * Colour::ONE + coat_value * Colour::ONE;
+        let pdf: f32 = lobes.probabilities[0] * wi.z / PI + lobes.probabilities[1] * specular_pdf + glass_pdf + lobes.probabilities[3] * coat_pdf;
+        (value, pdf)
+    }
+
+}
+
+impl material::Material for Principled {
+
+    //ONE LOBE IS SAMPLED, THE PDF IS THAT OF THE WHOLE MIXTURE SO THE WEIGHT STAYS LOW WHERE LOBES OVERLAP
+    fn scatter(&self, ray: &Ray, hit: &Hit, rng: &mut xorshift::StdRng) -> Scatter {
+        let frame: Frame = microfacet::frame(hit);
+        let wo: Vector3 = frame.to_local_vector(&-ray.direction.unit_vector());
+        if wo.z <= 0.0 {
+            return Scatter::None;
+        }
+        let lobes: Lobes = self.lobes(hit, &wo);
+
+        let u: f32 = rng.next_f32();
+        let [diffuse, specular, glass, _]: [f32; 4] = lobes.probabilities;
+        let wi: Vector3 = if u < diffuse {
+            let (r, phi): (f32, f32) = (rng.next_f32().sqrt(), 2.0 * PI * rng.next_f32());
+            V3!(r * phi.cos(), r * phi.sin(), f32::sqrt(f32::max(0.0, 1.0 - r * r)))
+        } else if u < diffuse + specular {
+            microfacet::reflect(&wo, &lobes.distribution.sample_visible(&wo, rng.next_f32(), rng.next_f32()))
+        } else if u < diffuse + specular + glass {
+            let m: Vector3 = lobes.distribution.sample_visible(&wo, rng.next_f32(), rng.next_f32());
+            if rng.next_f32() < dielectric::fresnel(Vector3::dot(&wo, &m), lobes.relative) {
+                microfacet::reflect(&wo, &m)
+            } else {
+                match microfacet::refract(&wo, &m, lobes.relative) {
+                    Some(wi) => wi,
+                    None => return Scatter::None,
+                }
+            }
+        } else {
+            microfacet::reflect(&wo, &lobes.coat.sample_visible(&wo, rng.next_f32(), rng.next_f32()))
+        };
+
+        let direction: Vector3 = frame.to_world_vector(&wi);
+        let Some((wo, wi)) = microfacet::directions(ray, hit, &direction) else { return Scatter::None };
+        let (value, pdf): (Colour, f32) = self.evaluate(&lobes, &wo, &wi);
+        if pdf <= 0.0 {
+            return Scatter::None;
+        }
+        Scatter::Sampled(value / pdf, Ray { origin: hit.point, direction, time: ray.time, differentials: None }, pdf)
+    }
+
+    fn eval(&self, ray: &Ray, hit: &Hit, direction: &Vector3) -> Colour {
+        let Some((wo, wi)) = microfacet::directions(ray, hit, direction) else { return Colour::ZERO };
+        self.evaluate(&self.lobes(hit, &wo), &wo, &wi).0
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &Hit, direction: &Vector3) -> f32 {
+        let Some((wo, wi)) = microfacet::directions(ray, hit, direction) else { return 0.0 };
+        self.evaluate(&self.lobes(hit, &wo), &wo, &wi).1
+    }
+}
\ No newline at end of file
@@ -0,0 +1,170 @@
+use std::ops;
+
+use crate::vector_3::{Vector3, Point3};
+
+//ROW MAJOR, ACTS ON COLUMN VECTORS
+#[derive(Debug, Copy, Clone)]
+pub struct Matrix4 {
+    pub m: [[f32; 4]; 4],
+}
+
+impl Matrix4 {
+    pub const IDENTITY: Matrix4 = Matrix4 { m: [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ] };
+
+    pub fn translation(offset: &Vector3) -> Matrix4 {
+        Matrix4 { m: [
+            [1.0, 0.0, 0.0, offset.x],
+            [0.0, 1.0, 0.0, offset.y],
+            [0.0, 0.0, 1.0, offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ] }
+    }
+
+    pub fn scaling(factors: &Vector3) -> Matrix4 {
+        Matrix4 { m: [
+            [factors.x, 0.0, 0.0, 0.0],
+            [0.0, factors.y, 0.0, 0.0],
+            [0.0, 0.0, factors.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ] }
+    }
+
+    //RIGHT HANDED, COUNTERCLOCKWISE LOOKING DOWN axis
+    pub fn rotation(axis: &Vector3, degrees: f32) -> Matrix4 {
+        let a: Vector3 = axis.unit_vector();
+        let (sin, cos): (f32, f32) = degrees.to_radians().sin_cos();
+        let t: f32 = 1.0 - cos;
+        Matrix4 { m: [
+            [t * a.x * a.x + cos, t * a.x * a.y - sin * a.z, t * a.x * a.z + sin * a.y, 0.0],
+            [t * a.x * a.y + sin * a.z, t * a.y * a.y + cos, t * a.y * a.z - sin * a.x, 0.0],
+            [t * a.x * a.z - sin * a.y, t * a.y * a.z + sin * a.x, t * a.z * a.z + cos, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ] }
+    }
+
+    pub fn transpose(&self) -> Matrix4 {
+        let mut m: [[f32; 4]; 4] = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Matrix4 { m }
+    }
+
+    //GAUSS-JORDAN WITH PARTIAL PIVOTING, NONE FOR SINGULAR MATRICES
+    pub fn inverse(&self) -> Option<Matrix4> {
+        let mut a: [[f32; 4]; 4] = self.m;
+        let mut inverse: [[f32; 4]; 4] = Matrix4::IDENTITY.m;
+
+        for column in 0..4 {
+            let pivot: usize = (column..4).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs())).unwrap();
+            if a[pivot][column].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(column, pivot);
+            inverse.swap(column, pivot);
+
+            let scale: f32 = 1.0 / a[column][column];
+            for j in 0..4 {
+                a[column][j] *= scale;
+                inverse[column][j] *= scale;
+            }
+            for row in 0..4 {
+                if row != column {
+                    let factor: f32 = a[row][column];
+                    for j in 0..4 {
+                        a[row][j] -= factor * a[column][j];
+                        inverse[row][j] -= factor * inverse[column][j];
+                    }
+                }
+            }
+        }
+        Some(Matrix4 { m: inverse })
+    }
+
+    //OF THE UPPER 3x3 BLOCK, THE SCALE FACTOR OF VOLUMES
+    pub fn determinant_3(&self) -> f32 {
+        let m: &[[f32; 4]; 4] = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn point(&self, p: &Point3) -> Point3 {
+        let m: &[[f32; 4]; 4] = &self.m;
+        let w: f32 = m[3][0] * p.x + m[3][1] * p.y + m[3][2] * p.z + m[3][3];
+        V3!(
+            m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3],
+            m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3],
+            m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3]
+        ) / w
+    }
+
+    //IGNORES THE TRANSLATION
+    pub fn vector(&self, v: &Vector3) -> Vector3 {
+        let m: &[[f32; 4]; 4] = &self.m;
+        V3!(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z
+        )
+    }
+}
+
+impl ops::Mul<Matrix4> for Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, rhs: Matrix4) -> Matrix4 {
+        let mut m: [[f32; 4]; 4] = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k: usize| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Matrix4 { m }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_identity(matrix: &Matrix4) {
+        for (i, row) in matrix.m.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                let expected: f32 = if i == j {1.0} else {0.0};
+                assert!((value - expected).abs() < 1e-5, "{:?}", matrix);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_the_matrix() {
+        let matrix: Matrix4 = Matrix4::translation(&V3!(3.0, -2.0, 7.0)) * Matrix4::rotation(&V3!(1.0, 2.0, 3.0), 40.0) * Matrix4::scaling(&V3!(2.0, 0.5, 3.0));
+        let inverse: Matrix4 = matrix.inverse().unwrap();
+        assert_identity(&(matrix * inverse));
+        assert_identity(&(inverse * matrix));
+    }
+
+    #[test]
+    fn inverse_needs_pivoting() {
+        //A ZERO ON THE DIAGONAL, WITHOUT ROW SWAPS THE ELIMINATION WOULD DIVIDE BY IT
+        let matrix: Matrix4 = Matrix4 { m: [
+            [0.0, 1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 2.0],
+            [0.0, 0.0, 4.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ] };
+        assert_identity(&(matrix * matrix.inverse().unwrap()));
+    }
+
+    #[test]
+    fn singular_matrices_have_no_inverse() {
+        assert!(Matrix4::scaling(&V3!(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+}
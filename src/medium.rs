@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use crate::material::Material;
+
+//HOMOGENEOUS, density IS THE EXTINCTION COEFFICIENT PER UNIT OF DISTANCE
+pub struct Medium {
+    pub density: f32,
+    pub phase: Arc<dyn Material>,
+}
+
+impl Medium {
+    //FREE FLIGHT DISTANCE FOR A UNIFORM u IN [0, 1)
+    pub fn sample_distance(&self, u: f32) -> f32 {
+        -f32::ln(1.0 - u) / self.density
+    }
+
+    pub fn transmittance(&self, distance: f32) -> f32 {
+        f32::exp(-self.density * distance)
+    }
+}
\ No newline at end of file
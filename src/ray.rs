@@ -1,13 +1,23 @@
 use crate::vector_3;
 
+//RAYS THROUGH THE NEIGHBOURING PIXELS, ONE STEP ALONG X AND ONE ALONG Y
+#[derive(Copy, Clone)]
+pub struct Differentials {
+    pub origin: [vector_3::Point3; 2],
+    pub direction: [vector_3::Vector3; 2],
+}
+
 #[derive(Copy, Clone)]
 pub struct Ray {
     pub origin    : vector_3::Point3 ,
     pub direction : vector_3::Vector3,
+    pub time      : f32,
+    //ONLY CAMERA RAYS CARRY THEM
+    pub differentials: Option<Differentials>,
 }
 
 impl Ray {
     pub fn at(&self, t: f32) -> vector_3::Point3 {
-        return self.origin + t * self.direction;
+        self.origin + t * self.direction
     }
 }
\ No newline at end of file
@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::colour::Colour;
+
+pub const TILE_SIZE: u16 = 32;
+
+#[derive(Copy, Clone)]
+pub struct Tile {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+//ROW MAJOR, j = 0 IS THE BOTTOM SCANLINE
+pub struct Framebuffer {
+    pub width: u16,
+    pub height: u16,
+    pub pixels: Vec<Colour>,
+}
+
+impl Framebuffer {
+    pub fn new(width: u16, height: u16) -> Framebuffer {
+        Framebuffer { width, height, pixels: vec![Colour::ZERO; width as usize * height as usize] }
+    }
+
+    pub fn get(&self, i: u16, j: u16) -> Colour {
+        self.pixels[j as usize * self.width as usize + i as usize]
+    }
+
+    fn set(&mut self, i: u16, j: u16, colour: Colour) {
+        self.pixels[j as usize * self.width as usize + i as usize] = colour;
+    }
+}
+
+fn tiles(width: u16, height: u16) -> Vec<Tile> {
+    let mut tiles: Vec<Tile> = vec![];
+    for y in (0..height).step_by(TILE_SIZE as usize) {
+        for x in (0..width).step_by(TILE_SIZE as usize) {
+            tiles.push(Tile { x, y, width: u16::min(TILE_SIZE, width - x), height: u16::min(TILE_SIZE, height - y) });
+        }
+    }
+    tiles
+}
+
+//EVERY TILE OWNS AN RNG SEEDED FROM ITS INDEX, SO THE IMAGE DOES NOT DEPEND ON THE THREAD COUNT
+fn tile_rng(seed: &[usize], index: usize) -> xorshift::StdRng {
+    let mut tile_seed: Vec<usize> = seed.to_vec();
+    tile_seed.push(index);
+    xorshift::SeedableRng::from_seed(&tile_seed[..])
+}
+
+pub fn render<F>(width: u16, height: u16, threads: usize, seed: &[usize], progress: bool, pixel: F) -> Framebuffer
+where
+    F: Fn(u16, u16, &mut xorshift::StdRng) -> Colour + Sync,
+{
+    let tiles: Vec<Tile> = tiles(width, height);
+    let next: AtomicUsize = AtomicUsize::new(0);
+    let remaining: AtomicUsize = AtomicUsize::new(tiles.len());
+
+    let rendered: Vec<(Tile, Vec<Colour>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..usize::max(threads, 1)).map(|_| scope.spawn(|| {
+            let mut rendered: Vec<(Tile, Vec<Colour>)> = vec![];
+            loop {
+                let index: usize = next.fetch_add(1, Ordering::Relaxed);
+                let Some(tile) = tiles.get(index) else { break };
+
+                let mut rng: xorshift::StdRng = tile_rng(seed, index);
+                let mut colours: Vec<Colour> = Vec::with_capacity(tile.width as usize * tile.height as usize);
+                for j in tile.y..tile.y + tile.height {
+                    for i in tile.x..tile.x + tile.width {
+                        colours.push(pixel(i, j, &mut rng));
+                    }
+                }
+                rendered.push((*tile, colours));
+                let remaining: usize = remaining.fetch_sub(1, Ordering::Relaxed) - 1;
+                if progress {
+                    eprintln!("tiles remaining: {}", remaining);
+                }
+            }
+            rendered
+        })).collect();
+
+        workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+    });
+
+    let mut framebuffer: Framebuffer = Framebuffer::new(width, height);
+    for (tile, colours) in rendered {
+        for (k, colour) in colours.into_iter().enumerate() {
+            let i: u16 = tile.x + (k % tile.width as usize) as u16;
+            let j: u16 = tile.y + (k / tile.width as usize) as u16;
+            framebuffer.set(i, j, colour);
+        }
+    }
+    framebuffer
+}
@@ -1 +1,85 @@
-pub mod random_spheres;
\ No newline at end of file
+use std::sync::Arc;
+
+use crate::background::Background;
+use crate::hit::{Surface, HitList};
+use crate::geometry::triangle::TriangleMesh;
+use crate::camera::Camera;
+use crate::light::Lights;
+use crate::medium::Medium;
+
+pub mod random_spheres;
+pub mod description;
+
+pub struct Settings {
+    pub width: u16,
+    pub height: u16,
+    pub samples: u16,
+    pub depth: u16,
+}
+
+pub struct Scene {
+    pub world: HitList,
+    pub camera: Box<dyn Camera>,
+    pub settings: Settings,
+    //SHARED WITH THE LIGHTS WHEN IT IS IMPORTANCE SAMPLED
+    pub background: Arc<dyn Background>,
+    pub lights: Lights,
+    //HAZE AROUND EVERYTHING
+    pub medium: Option<Medium>,
+}
+
+impl Scene {
+    //A SINGLE DIMENSION KEEPS THE ASPECT RATIO, BOTH STRETCH THE CAMERA TO MATCH
+    pub fn resize(&mut self, width: Option<u16>, height: Option<u16>) {
+        let aspect_ratio: f32 = self.settings.width as f32 / self.settings.height as f32;
+        let (width, height): (u16, u16) = match (width, height) {
+            (None, None) => return,
+            (Some(width), None) => (width, u16::max((width as f32 / aspect_ratio) as u16, 2)),
+            (None, Some(height)) => (u16::max((height as f32 * aspect_ratio) as u16, 2), height),
+            (Some(width), Some(height)) => {
+                self.camera.set_aspect_ratio(width as f32 / height as f32);
+                (width, height)
+            }
+        };
+        self.settings.width = width;
+        self.settings.height = height;
+    }
+}
+
+//OBJECTS AND THE EMISSIVE SURFACES AMONG THEM
+#[derive(Default)]
+pub struct Group {
+    pub world: HitList,
+    pub lights: Lights,
+    //INSTANCES NUMBERED SO FAR, NESTED GROUPS CONTINUE THE COUNT
+    pub instances: usize,
+}
+
+impl Group {
+    pub fn nested(&self) -> Group {
+        Group { instances: self.instances, ..Group::default() }
+    }
+
+    pub fn next_instance(&mut self) -> usize {
+        self.instances += 1;
+        self.instances - 1
+    }
+
+    //EMISSIVE SURFACES ARE SHARED WITH THE LIGHTS SO THEY CAN BE SAMPLED DIRECTLY
+    pub fn add(&mut self, surface: impl Surface + 'static, emissive: bool) {
+        if emissive {
+            let surface: Arc<dyn Surface> = Arc::new(surface);
+            self.lights.surfaces.push(surface.clone());
+            self.world.add(surface);
+        } else {
+            self.world.add(surface);
+        }
+    }
+
+    pub fn add_mesh(&mut self, mesh: &Arc<TriangleMesh>) {
+        for triangle in TriangleMesh::triangles(mesh) {
+            let emissive: bool = mesh.materials[triangle.index].emissive();
+            self.add(triangle, emissive);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,1226 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::bvh::BvhNode;
+use crate::camera::{Camera, Shutter, look_at};
+use crate::camera::perspective::Perspective;
+use crate::camera::orthographic::Orthographic;
+use crate::camera::fisheye::{Fisheye, Mapping};
+use crate::camera::equirectangular::Equirectangular;
+use crate::camera::cylindrical::Cylindrical;
+use crate::geometry::sphere::{Sphere, MovingSphere};
+use crate::geometry::triangle::{Triangle, TriangleMesh};
+use crate::geometry::transformed::{Transformed, Animated};
+use crate::geometry::plane::Plane;
+use crate::geometry::quad::Quad;
+use crate::geometry::disk::Disk;
+use crate::geometry::cuboid::Cuboid;
+use crate::geometry::cylinder::Cylinder;
+use crate::geometry::cone::Cone;
+use crate::geometry::torus::Torus;
+use crate::geometry::csg::{Csg, Operation};
+use crate::geometry::frame::Frame;
+use crate::aabb::Aabb;
+use crate::hit::Surface;
+use crate::import::ImportError;
+use crate::import::obj::load_obj;
+use crate::import::ies::load_ies;
+use crate::material::Material;
+use crate::material::lambertian::Lambertian;
+use crate::material::metallic::Metallic;
+use crate::material::dielectric::Dielectric;
+use crate::material::diffuse_light::DiffuseLight;
+use crate::material::isotropic::Isotropic;
+use crate::material::conductor::{Conductor, Metal};
+use crate::material::microfacet::Ggx;
+use crate::material::principled::Principled;
+use crate::medium::Medium;
+use crate::geometry::constant_medium::ConstantMedium;
+use crate::texture::Texture;
+use crate::texture::solid_colour::SolidColour;
+use crate::texture::checker::Checker;
+use crate::texture::noise::{Noise, Perlin};
+use crate::texture::image_texture::{ImageTexture, Wrap};
+use crate::texture::variation::{Variation, Key};
+use crate::import::image::load_image;
+use crate::colour::Transfer;
+use crate::scene::{Scene, Settings, Group};
+use crate::background::Background;
+use crate::background::gradient::Gradient;
+use crate::background::solid::Solid;
+use crate::background::environment::Environment;
+use crate::background::physical_sky::PhysicalSky;
+use crate::light::point::Point;
+use crate::light::spot::Spot;
+use crate::light::directional::Directional;
+use crate::light::profile::Profile;
+use crate::matrix_4::Matrix4;
+use crate::transform::{Transform, Step, Motion};
+use crate::vector_3::{Vector3, Point3};
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Invalid(PathBuf, String),
+    UnknownMaterial(PathBuf, String),
+    UnknownTexture(PathBuf, String),
+    UnknownPrototype(PathBuf, String),
+    Import(ImportError),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            SceneError::Parse(path, error) => write!(f, "{}: {}", path.display(), error),
+            SceneError::Invalid(path, message) => write!(f, "{}: {}", path.display(), message),
+            SceneError::UnknownMaterial(path, name) => write!(f, "{}: unknown material '{}'", path.display(), name),
+            SceneError::UnknownTexture(path, name) => write!(f, "{}: unknown texture '{}'", path.display(), name),
+            SceneError::UnknownPrototype(path, name) => write!(f, "{}: unknown prototype '{}'", path.display(), name),
+            SceneError::Import(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<ImportError> for SceneError {
+    fn from(error: ImportError) -> SceneError {
+        SceneError::Import(error)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    render: RenderFile,
+    camera: CameraFile,
+    #[serde(default)]
+    background: BackgroundFile,
+    medium: Option<MediumFile>,
+    #[serde(default)]
+    textures: HashMap<String, TextureFile>,
+    #[serde(default)]
+    materials: HashMap<String, MaterialFile>,
+    #[serde(default)]
+    prototypes: HashMap<String, PrototypeFile>,
+    #[serde(default)]
+    objects: Vec<ObjectFile>,
+    #[serde(default)]
+    lights: Vec<LightFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RenderFile {
+    width: u16,
+    height: u16,
+    samples: u16,
+    depth: u16,
+}
+
+fn default_up() -> [f32; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraFile {
+    origin: [f32; 3],
+    target: [f32; 3],
+    #[serde(default = "default_up")]
+    up: [f32; 3],
+    #[serde(default)]
+    projection: ProjectionFile,
+    //DEGREES, FOR PERSPECTIVE AND CYLINDRICAL PROJECTIONS ONLY
+    vertical_fov: Option<f32>,
+    //DEPTH OF FIELD, FOR PERSPECTIVE PROJECTIONS ONLY
+    #[serde(default)]
+    aperture: f32,
+    focus_distance: Option<f32>,
+    //OPEN AND CLOSE TIMES, WITHIN THE 0 TO 1 SPAN OF THE KEYFRAMES
+    #[serde(default)]
+    shutter: [f32; 2],
+}
+
+#[derive(Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ProjectionFile {
+    #[default]
+    Perspective,
+    //PARALLEL RAYS FROM A RECTANGLE height UNITS TALL AROUND THE ORIGIN
+    Orthographic { height: f32 },
+    //A CIRCLE AS TALL AS THE IMAGE COVERING fov DEGREES, UP TO 360
+    Fisheye {
+        #[serde(default = "default_fisheye_fov")]
+        fov: f32,
+        #[serde(default)]
+        mapping: MappingFile,
+    },
+    //EVERY DIRECTION, BEST AT A 2:1 ASPECT RATIO
+    Equirectangular,
+    //ALL THE WAY ROUND HORIZONTALLY, vertical_fov UP AND DOWN
+    Cylindrical,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum MappingFile {
+    #[default]
+    Equidistant,
+    Equisolid,
+}
+
+fn default_fisheye_fov() -> f32 {
+    180.0
+}
+
+#[derive(Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum BackgroundFile {
+    #[default]
+    Sky,
+    Solid { colour: [f32; 3] },
+    //EQUIRECTANGULAR .hdr OR .pfm IMAGE, TURNED rotation DEGREES ABOUT +Y, SAMPLED AS A LIGHT
+    Environment {
+        path: PathBuf,
+        #[serde(default)]
+        rotation: f32,
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    //CLEAR DAYLIGHT WITH THE SUN elevation DEGREES ABOVE THE HORIZON, azimuth DEGREES FROM -Z TOWARDS +X, SAMPLED AS A LIGHT
+    PhysicalSky {
+        elevation: f32,
+        #[serde(default)]
+        azimuth: f32,
+        #[serde(default = "default_turbidity")]
+        turbidity: f32,
+        //DEGREES
+        #[serde(default = "default_sun_diameter")]
+        sun_diameter: f32,
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+}
+
+fn default_intensity() -> f32 {
+    1.0
+}
+
+fn default_turbidity() -> f32 {
+    3.0
+}
+
+fn default_sun_diameter() -> f32 {
+    0.53
+}
+
+//material SHOULD BE ISOTROPIC, THE PHASE FUNCTION OF THE MEDIUM
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MediumFile {
+    density: f32,
+    material: String,
+}
+
+//A PLAIN COLOUR OR THE NAME OF A TEXTURE
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a colour or the name of a texture")]
+enum TextureValue {
+    Colour([f32; 3]),
+    Named(String),
+}
+
+//A PLAIN NUMBER OR THE NAME OF A TEXTURE WHOSE CHANNELS ARE AVERAGED
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a number or the name of a texture")]
+enum ScalarValue {
+    Number(f32),
+    Named(String),
+}
+
+fn default_zero() -> ScalarValue {
+    ScalarValue::Number(0.0)
+}
+
+fn default_half() -> ScalarValue {
+    ScalarValue::Number(0.5)
+}
+
+fn default_clearcoat_roughness() -> f32 {
+    0.1
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum WrapFile {
+    #[default]
+    Repeat,
+    Mirror,
+    Clamp,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum KeyFile {
+    #[default]
+    Instance,
+    Primitive,
+}
+
+fn default_octaves() -> u32 {
+    1
+}
+
+fn default_white() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum TextureFile {
+    Solid { colour: [f32; 3] },
+    Checker { odd: TextureValue, even: TextureValue, size: f32 },
+    Noise {
+        #[serde(default = "default_white")]
+        colour: [f32; 3],
+        scale: f32,
+        #[serde(default = "default_octaves")]
+        octaves: u32,
+        #[serde(default)]
+        seed: usize,
+    },
+    //8 BIT AND 16 BIT IMAGES ARE ASSUMED TO BE SRGB ENCODED UNLESS linear IS SET
+    Image {
+        path: PathBuf,
+        #[serde(default)]
+        wrap: WrapFile,
+        #[serde(default)]
+        linear: bool,
+    },
+    //ONE OF textures FOR EACH INSTANCE, OR FOR EACH MESH TRIANGLE
+    Variation {
+        textures: Vec<TextureValue>,
+        #[serde(default)]
+        by: KeyFile,
+    },
+}
+
+impl TextureFile {
+    fn references(&self) -> Vec<&String> {
+        match self {
+            TextureFile::Checker { odd, even, .. } => [odd, even].into_iter()
+                .filter_map(|value: &TextureValue| match value {
+                    TextureValue::Named(name) => Some(name),
+                    TextureValue::Colour(_) => None,
+                })
+                .collect(),
+            TextureFile::Variation { textures, .. } => textures.iter()
+                .filter_map(|value: &TextureValue| match value {
+                    TextureValue::Named(name) => Some(name),
+                    TextureValue::Colour(_) => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum MaterialFile {
+    Lambertian { albedo: TextureValue },
+    Metallic { albedo: TextureValue, #[serde(default)] fuzziness: f32 },
+    //tint IS THE COLOUR LEFT AFTER absorption_distance INSIDE, CLEAR WITHOUT ONE
+    Dielectric {
+        refraction_index: f32,
+        #[serde(default)]
+        roughness: RoughnessFile,
+        tint: Option<[f32; 3]>,
+        absorption_distance: Option<f32>,
+    },
+    DiffuseLight { emit: [f32; 3] },
+    Isotropic { albedo: TextureValue },
+    //EITHER A metal PRESET OR BOTH eta AND k
+    Conductor {
+        metal: Option<MetalFile>,
+        eta: Option<[f32; 3]>,
+        k: Option<[f32; 3]>,
+        #[serde(default)]
+        roughness: RoughnessFile,
+    },
+    //PARAMETERS BETWEEN 0 AND 1, ALL BUT clearcoat_roughness CAN COME FROM TEXTURES
+    Principled {
+        base_colour: TextureValue,
+        #[serde(default = "default_zero")]
+        metallic: ScalarValue,
+        #[serde(default = "default_half")]
+        roughness: ScalarValue,
+        #[serde(default = "default_half")]
+        specular: ScalarValue,
+        #[serde(default = "default_zero")]
+        sheen: ScalarValue,
+        #[serde(default = "default_zero")]
+        clearcoat: ScalarValue,
+        #[serde(default = "default_clearcoat_roughness")]
+        clearcoat_roughness: f32,
+        #[serde(default = "default_zero")]
+        transmission: ScalarValue,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MetalFile {
+    Gold,
+    Silver,
+    Copper,
+    Aluminium,
+    Iron,
+    Chromium,
+}
+
+//ONE ROUGHNESS, OR ONE ALONG THE TANGENT AND ONE ACROSS IT
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a roughness or a pair of roughnesses")]
+enum RoughnessFile {
+    Isotropic(f32),
+    Anisotropic([f32; 2]),
+}
+
+impl Default for RoughnessFile {
+    fn default() -> RoughnessFile {
+        RoughnessFile::Isotropic(0.0)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ObjectFile {
+    Sphere { center: [f32; 3], radius: f32, material: String },
+    //CENTERS AT TIME 0 AND 1
+    MovingSphere { center: [[f32; 3]; 2], radius: f32, material: String },
+    Triangle { vertices: [[f32; 3]; 3], material: String },
+    Plane { point: [f32; 3], normal: [f32; 3], material: String },
+    //PARALLELOGRAM SPANNED BY u AND v FROM corner
+    Quad { corner: [f32; 3], u: [f32; 3], v: [f32; 3], material: String },
+    Disk { center: [f32; 3], normal: [f32; 3], radius: f32, material: String },
+    //AXIS ALIGNED, GIVEN BY ANY TWO OPPOSITE CORNERS
+    #[serde(rename = "box")]
+    Cuboid { min: [f32; 3], max: [f32; 3], material: String },
+    Cylinder { base: [f32; 3], top: [f32; 3], radius: f32, material: String },
+    Cone { base: [f32; 3], apex: [f32; 3], radius: f32, material: String },
+    Torus { center: [f32; 3], normal: [f32; 3], major_radius: f32, minor_radius: f32, material: String },
+    Mesh {
+        positions: Vec<[f32; 3]>,
+        indices: Vec<[usize; 3]>,
+        #[serde(default)]
+        normals: Vec<[f32; 3]>,
+        #[serde(default)]
+        uvs: Vec<[f32; 2]>,
+        material: String,
+    },
+    //MATERIAL IS USED FOR FACES WITHOUT A usemtl
+    Obj { path: PathBuf, material: String },
+    //boundary MUST BE CLOSED, ITS MATERIAL IS NEVER SEEN
+    ConstantMedium { boundary: Box<ObjectFile>, density: f32, material: String },
+    //left AND right MUST BE CLOSED, EACH KEEPS ITS OWN MATERIAL
+    Csg { operation: OperationFile, left: Box<ObjectFile>, right: Box<ObjectFile> },
+    Instance {
+        prototype: String,
+        #[serde(default)]
+        transform: Vec<TransformFile>,
+        //WHERE transform ENDS UP AT TIME 1, STEP BY STEP
+        #[serde(default)]
+        motion: Vec<TransformFile>,
+    },
+}
+
+//colour TIMES intensity IS THE INTENSITY OF POINTS AND SPOTS, THE IRRADIANCE OF DIRECTIONAL LIGHTS AND THE RADIANCE OF THE REST
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum LightFile {
+    Point {
+        position: [f32; 3],
+        #[serde(default = "default_white")]
+        colour: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    //cone_angle DEGREES FROM THE AXIS TO WHERE THE LIGHT ENDS, FADING OVER THE LAST cone_delta
+    //profile IS AN IES FILE OF CANDELAS THAT colour TIMES intensity SCALES, WITHOUT A cone_angle IT ALONE SHAPES THE BEAM
+    Spot {
+        position: [f32; 3],
+        target: [f32; 3],
+        #[serde(default = "default_white")]
+        colour: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+        cone_angle: Option<f32>,
+        #[serde(default = "default_cone_delta")]
+        cone_delta: f32,
+        profile: Option<PathBuf>,
+    },
+    //direction IS THE WAY THE LIGHT TRAVELS
+    Directional {
+        direction: [f32; 3],
+        #[serde(default = "default_white")]
+        colour: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    Sphere {
+        center: [f32; 3],
+        radius: f32,
+        #[serde(default = "default_white")]
+        colour: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    Quad {
+        corner: [f32; 3],
+        u: [f32; 3],
+        v: [f32; 3],
+        #[serde(default = "default_white")]
+        colour: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    Disk {
+        center: [f32; 3],
+        normal: [f32; 3],
+        radius: f32,
+        #[serde(default = "default_white")]
+        colour: [f32; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+}
+
+fn default_cone_angle() -> f32 {
+    30.0
+}
+
+fn default_cone_delta() -> f32 {
+    5.0
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum OperationFile {
+    Union,
+    Intersection,
+    Difference,
+}
+
+//OBJECTS BUILT ONCE AND SHARED BY EVERY INSTANCE
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PrototypeFile {
+    objects: Vec<ObjectFile>,
+}
+
+impl ObjectFile {
+    fn references(&self) -> Vec<&String> {
+        match self {
+            ObjectFile::Instance { prototype, .. } => vec![prototype],
+            ObjectFile::ConstantMedium { boundary, .. } => boundary.references(),
+            ObjectFile::Csg { left, right, .. } => left.references().into_iter().chain(right.references()).collect(),
+            _ => vec![],
+        }
+    }
+}
+
+impl PrototypeFile {
+    fn references(&self) -> Vec<&String> {
+        self.objects.iter().flat_map(|object: &ObjectFile| object.references()).collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a scale factor or one factor per axis")]
+enum ScaleFile {
+    Uniform(f32),
+    Axes([f32; 3]),
+}
+
+//APPLIED IN THE ORDER THEY ARE LISTED
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum TransformFile {
+    Translate([f32; 3]),
+    Rotate { axis: [f32; 3], degrees: f32 },
+    Scale(ScaleFile),
+    Matrix([[f32; 4]; 4]),
+}
+
+struct Prototype {
+    surface: Arc<dyn Surface>,
+    lights: Vec<Arc<dyn Surface>>,
+}
+
+fn vector(v: [f32; 3]) -> Vector3 {
+    V3!(v[0], v[1], v[2])
+}
+
+fn scalar(path: &Path, name: &str, parameter: &str, value: ScalarValue, textures: &HashMap<String, Arc<dyn Texture>>) -> Result<Arc<dyn Texture>, SceneError> {
+    match value {
+        ScalarValue::Number(number) if !(0.0..=1.0).contains(&number) =>
+            Err(SceneError::Invalid(path.to_path_buf(), format!("material '{}' {} {} must be between 0 and 1", name, parameter, number))),
+        ScalarValue::Number(number) => Ok(Arc::new(SolidColour{colour: number * Vector3::ONE})),
+        ScalarValue::Named(texture) => textures.get(&texture).cloned().ok_or(SceneError::UnknownTexture(path.to_path_buf(), texture)),
+    }
+}
+
+fn roughness_pair(path: &Path, name: &str, roughness: RoughnessFile) -> Result<(f32, f32), SceneError> {
+    let roughness: (f32, f32) = match roughness {
+        RoughnessFile::Isotropic(roughness) => (roughness, roughness),
+        RoughnessFile::Anisotropic([along, across]) => (along, across),
+    };
+    if !(0.0..=1.0).contains(&roughness.0) || !(0.0..=1.0).contains(&roughness.1) {
+        return Err(SceneError::Invalid(path.to_path_buf(), format!("material '{}' roughness must be between 0 and 1", name)));
+    }
+    Ok(roughness)
+}
+
+fn steps(path: &Path, steps: Vec<TransformFile>) -> Result<Vec<Step>, SceneError> {
+    let invalid = |message: &str| SceneError::Invalid(path.to_path_buf(), message.to_string());
+    steps.into_iter().map(|step: TransformFile| -> Result<Step, SceneError> {
+        match step {
+            TransformFile::Translate(offset) => Ok(Step::Translate(vector(offset))),
+            TransformFile::Rotate { axis, degrees } => {
+                if vector(axis).length_squared() == 0.0 {
+                    return Err(invalid("rotation axis must not be zero"));
+                }
+                Ok(Step::Rotate(vector(axis), degrees))
+            }
+            TransformFile::Scale(ScaleFile::Uniform(factor)) => Ok(Step::Scale(factor * Vector3::ONE)),
+            TransformFile::Scale(ScaleFile::Axes(factors)) => Ok(Step::Scale(vector(factors))),
+            TransformFile::Matrix(m) => {
+                //NORMALS, BOUNDS AND DISTANCES ALONG RAYS ALL ASSUME THE MAP IS AFFINE
+                if m[3] != [0.0, 0.0, 0.0, 1.0] {
+                    return Err(invalid("transform matrix must be affine, with a last row of [0, 0, 0, 1]"));
+                }
+                Transform::new(Matrix4 { m }).map(Step::Matrix).ok_or(invalid("transform is singular"))
+            }
+        }
+    }).collect()
+}
+
+fn texture(path: &Path, value: TextureValue, textures: &HashMap<String, Arc<dyn Texture>>) -> Result<Arc<dyn Texture>, SceneError> {
+    match value {
+        TextureValue::Colour(colour) => Ok(Arc::new(SolidColour{colour: vector(colour)})),
+        TextureValue::Named(name) => textures.get(&name).cloned().ok_or(SceneError::UnknownTexture(path.to_path_buf(), name)),
+    }
+}
+
+pub fn load_scene(path: &Path) -> Result<Scene, SceneError> {
+    let source: String = std::fs::read_to_string(path).map_err(|error| SceneError::Io(path.to_path_buf(), error))?;
+    let file: SceneFile = toml::from_str(&source).map_err(|error| SceneError::Parse(path.to_path_buf(), error))?;
+    let invalid = |message: String| SceneError::Invalid(path.to_path_buf(), message);
+
+    //RENDER
+    let render: RenderFile = file.render;
+    if render.width < 2 || render.height < 2 {
+        return Err(invalid(format!("resolution {}x{} must be at least 2x2", render.width, render.height)));
+    }
+    if render.samples == 0 {
+        return Err(invalid("samples must be at least 1".to_string()));
+    }
+    let settings: Settings = Settings { width: render.width, height: render.height, samples: render.samples, depth: render.depth };
+
+    //CAMERA
+    let camera: Box<dyn Camera> = load_camera(path, file.camera, render.width as f32 / render.height as f32)?;
+
+    //BACKGROUND
+    //THE SECOND ONE IS SHARED WITH THE LIGHTS
+    let (background, environment): (Arc<dyn Background>, Option<Arc<dyn Background>>) = match file.background {
+        BackgroundFile::Sky => (Arc::new(Gradient), None),
+        BackgroundFile::Solid { colour } => (Arc::new(Solid{colour: vector(colour)}), None),
+        BackgroundFile::Environment { path: image, rotation, intensity } => {
+            if intensity < 0.0 {
+                return Err(invalid(format!("background intensity {} must not be negative", intensity)));
+            }
+            let image: PathBuf = path.parent().unwrap_or(Path::new("")).join(image);
+            let environment: Arc<dyn Background> = Arc::new(Environment::new(load_image(&image, Transfer::Linear)?, rotation.to_radians(), intensity));
+            (environment.clone(), Some(environment))
+        }
+        BackgroundFile::PhysicalSky { elevation, azimuth, turbidity, sun_diameter, intensity } => {
+            if !(0.0..=90.0).contains(&elevation) {
+                return Err(invalid(format!("background elevation {} must be between 0 and 90", elevation)));
+            }
+            if !(1.7..=10.0).contains(&turbidity) {
+                return Err(invalid(format!("background turbidity {} must be between 1.7 and 10", turbidity)));
+            }
+            if !(sun_diameter > 0.0 && sun_diameter <= 20.0) {
+                return Err(invalid(format!("background sun_diameter {} must be above 0 and at most 20", sun_diameter)));
+            }
+            if intensity < 0.0 {
+                return Err(invalid(format!("background intensity {} must not be negative", intensity)));
+            }
+            let sky: Arc<dyn Background> = Arc::new(PhysicalSky::new(elevation.to_radians(), azimuth.to_radians(), turbidity, sun_diameter.to_radians(), intensity));
+            (sky.clone(), Some(sky))
+        }
+    };
+
+    //TEXTURES, EACH ONE BUILT AFTER THE TEXTURES IT REFERS TO
+    let mut pending: Vec<(String, TextureFile)> = file.textures.into_iter().collect();
+    for (_, texture) in pending.iter() {
+        if let Some(name) = texture.references().into_iter().find(|name: &&String| !pending.iter().any(|(other, _)| other == *name)) {
+            return Err(SceneError::UnknownTexture(path.to_path_buf(), name.clone()));
+        }
+    }
+    let mut textures: HashMap<String, Arc<dyn Texture>> = HashMap::new();
+    while !pending.is_empty() {
+        let (ready, waiting): (Vec<_>, Vec<_>) = pending.into_iter()
+            .partition(|(_, texture)| texture.references().into_iter().all(|name: &String| textures.contains_key(name)));
+        if ready.is_empty() {
+            let mut names: Vec<String> = waiting.into_iter().map(|(name, _)| format!("'{}'", name)).collect();
+            names.sort();
+            return Err(invalid(format!("textures {} form a reference cycle", names.join(", "))));
+        }
+
+        for (name, definition) in ready {
+            let built: Arc<dyn Texture> = match definition {
+                TextureFile::Solid { colour } => Arc::new(SolidColour{colour: vector(colour)}),
+                TextureFile::Checker { odd, even, size } => {
+                    if size <= 0.0 {
+                        return Err(invalid(format!("texture '{}' size {} must be positive", name, size)));
+                    }
+                    Arc::new(Checker{odd: texture(path, odd, &textures)?, even: texture(path, even, &textures)?, size})
+                }
+                TextureFile::Noise { colour, scale, octaves, seed } => {
+                    if scale <= 0.0 {
+                        return Err(invalid(format!("texture '{}' scale {} must be positive", name, scale)));
+                    }
+                    let mut rng: xorshift::StdRng = xorshift::SeedableRng::from_seed(&[seed][..]);
+                    Arc::new(Noise{perlin: Perlin::new(&mut rng), colour: vector(colour), scale, octaves})
+                }
+                TextureFile::Image { path: image, wrap, linear } => {
+                    let image: PathBuf = path.parent().unwrap_or(Path::new("")).join(image);
+                    let transfer: Transfer = if linear {Transfer::Linear} else {Transfer::Srgb};
+                    let wrap: Wrap = match wrap {
+                        WrapFile::Repeat => Wrap::Repeat,
+                        WrapFile::Mirror => Wrap::Mirror,
+                        WrapFile::Clamp => Wrap::Clamp,
+                    };
+                    Arc::new(ImageTexture{image: load_image(&image, transfer)?, wrap})
+                }
+                TextureFile::Variation { textures: choices, by } => {
+                    if choices.is_empty() {
+                        return Err(invalid(format!("texture '{}' needs at least one texture to vary between", name)));
+                    }
+                    let key: Key = match by {
+                        KeyFile::Instance => Key::Instance,
+                        KeyFile::Primitive => Key::Primitive,
+                    };
+                    let choices: Vec<Arc<dyn Texture>> = choices.into_iter()
+                        .map(|choice: TextureValue| texture(path, choice, &textures))
+                        .collect::<Result<_, _>>()?;
+                    Arc::new(Variation{textures: choices, key})
+                }
+            };
+            textures.insert(name, built);
+        }
+        pending = waiting;
+    }
+
+    //MATERIALS
+    let mut materials: HashMap<String, Arc<dyn Material>> = HashMap::new();
+    for (name, material) in file.materials {
+        let material: Arc<dyn Material> = match material {
+            MaterialFile::Lambertian { albedo } => Arc::new(Lambertian{albedo: texture(path, albedo, &textures)?}),
+            MaterialFile::Metallic { albedo, fuzziness } => {
+                if fuzziness < 0.0 {
+                    return Err(invalid(format!("material '{}' fuzziness {} must not be negative", name, fuzziness)));
+                }
+                Arc::new(Metallic{albedo: texture(path, albedo, &textures)?, fuzziness})
+            }
+            MaterialFile::Dielectric { refraction_index, roughness, tint, absorption_distance } => {
+                if refraction_index <= 0.0 {
+                    return Err(invalid(format!("material '{}' refraction_index {} must be positive", name, refraction_index)));
+                }
+                let absorption: Vector3 = match (tint, absorption_distance) {
+                    (None, None) => Vector3::ZERO,
+                    (None, Some(_)) => return Err(invalid(format!("material '{}' absorption_distance needs a tint", name))),
+                    (Some(tint), distance) => {
+                        let distance: f32 = distance.unwrap_or(1.0);
+                        if distance <= 0.0 {
+                            return Err(invalid(format!("material '{}' absorption_distance {} must be positive", name, distance)));
+                        }
+                        if tint.iter().any(|channel: &f32| *channel <= 0.0 || *channel > 1.0) {
+                            return Err(invalid(format!("material '{}' tint must be above 0 and at most 1", name)));
+                        }
+                        V3!(-f32::ln(tint[0]), -f32::ln(tint[1]), -f32::ln(tint[2])) / distance
+                    }
+                };
+                Arc::new(Dielectric{refraction_index, distribution: Ggx::from_roughness(roughness_pair(path, &name, roughness)?), absorption})
+            }
+            MaterialFile::DiffuseLight { emit } => Arc::new(DiffuseLight{emit: vector(emit)}),
+            MaterialFile::Isotropic { albedo } => Arc::new(Isotropic{albedo: texture(path, albedo, &textures)?}),
+            MaterialFile::Conductor { metal, eta, k, roughness } => {
+                let (eta, k): (Vector3, Vector3) = match (metal, eta, k) {
+                    (Some(metal), None, None) => match metal {
+                        MetalFile::Gold => Metal::Gold,
+                        MetalFile::Silver => Metal::Silver,
+                        MetalFile::Copper => Metal::Copper,
+                        MetalFile::Aluminium => Metal::Aluminium,
+                        MetalFile::Iron => Metal::Iron,
+                        MetalFile::Chromium => Metal::Chromium,
+                    }.ior(),
+                    (None, Some(eta), Some(k)) => (vector(eta), vector(k)),
+                    _ => return Err(invalid(format!("material '{}' needs either metal or both eta and k", name))),
+                };
+                if [eta.x, eta.y, eta.z, k.x, k.y, k.z].iter().any(|value: &f32| *value < 0.0) {
+                    return Err(invalid(format!("material '{}' eta and k must not be negative", name)));
+                }
+                Arc::new(Conductor{eta, k, distribution: Ggx::from_roughness(roughness_pair(path, &name, roughness)?)})
+            }
+            MaterialFile::Principled { base_colour, metallic, roughness, specular, sheen, clearcoat, clearcoat_roughness, transmission } => {
+                if !(0.0..=1.0).contains(&clearcoat_roughness) {
+                    return Err(invalid(format!("material '{}' clearcoat_roughness {} must be between 0 and 1", name, clearcoat_roughness)));
+                }
+                Arc::new(Principled{
+                    base_colour: texture(path, base_colour, &textures)?,
+                    metallic: scalar(path, &name, "metallic", metallic, &textures)?,
+                    roughness: scalar(path, &name, "roughness", roughness, &textures)?,
+                    specular: scalar(path, &name, "specular", specular, &textures)?,
+                    sheen: scalar(path, &name, "sheen", sheen, &textures)?,
+                    clearcoat: scalar(path, &name, "clearcoat", clearcoat, &textures)?,
+                    clearcoat_roughness,
+                    transmission: scalar(path, &name, "transmission", transmission, &textures)?,
+                })
+            }
+        };
+        materials.insert(name, material);
+    }
+    //PROTOTYPES, EACH ONE BUILT AFTER THE PROTOTYPES IT INSTANCES
+    let mut pending: Vec<(String, PrototypeFile)> = file.prototypes.into_iter().collect();
+    for (_, prototype) in pending.iter() {
+        if let Some(name) = prototype.references().into_iter().find(|name: &&String| !pending.iter().any(|(other, _)| other == *name)) {
+            return Err(SceneError::UnknownPrototype(path.to_path_buf(), name.clone()));
+        }
+    }
+    let mut prototypes: HashMap<String, Prototype> = HashMap::new();
+    while !pending.is_empty() {
+        let (ready, waiting): (Vec<_>, Vec<_>) = pending.into_iter()
+            .partition(|(_, prototype)| prototype.references().into_iter().all(|name: &String| prototypes.contains_key(name)));
+        if ready.is_empty() {
+            let mut names: Vec<String> = waiting.into_iter().map(|(name, _)| format!("'{}'", name)).collect();
+            names.sort();
+            return Err(invalid(format!("prototypes {} form a reference cycle", names.join(", "))));
+        }
+
+        for (name, definition) in ready {
+            if definition.objects.is_empty() {
+                return Err(invalid(format!("prototype '{}' has no objects", name)));
+            }
+            let mut group: Group = Group::default();
+            for object in definition.objects {
+                add_object(path, object, &materials, &prototypes, &mut group)?;
+            }
+            let surface: Arc<dyn Surface> = Arc::new(BvhNode::new(group.world));
+            prototypes.insert(name, Prototype { surface, lights: group.lights.surfaces });
+        }
+        pending = waiting;
+    }
+
+    //OBJECTS
+    let mut group: Group = Group::default();
+    for object in file.objects {
+        add_object(path, object, &materials, &prototypes, &mut group)?;
+    }
+    for light in file.lights {
+        add_light(path, light, &mut group)?;
+    }
+    group.lights.environment = environment;
+    //HALF THE DIAGONAL OF EVERYTHING BOUNDED
+    let bounds: Option<Aabb> = group.world.objects.iter().filter_map(|object| object.bounding_box()).reduce(|a: Aabb, b: Aabb| Aabb::union(&a, &b));
+    group.lights.build(bounds.map_or(1.0, |bounds: Aabb| 0.5 * (bounds.max - bounds.min).length()));
+
+    //MEDIUM
+    let medium: Option<Medium> = match file.medium {
+        Some(MediumFile { density, material: name }) => {
+            if density <= 0.0 {
+                return Err(invalid(format!("medium density {} must be positive", density)));
+            }
+            let phase: Arc<dyn Material> = materials.get(&name).cloned().ok_or(SceneError::UnknownMaterial(path.to_path_buf(), name))?;
+            Some(Medium { density, phase })
+        }
+        None => None,
+    };
+
+    Ok(Scene { world: group.world, camera, settings, background, lights: group.lights, medium })
+}
+
+fn add_object(path: &Path, object: ObjectFile, materials: &HashMap<String, Arc<dyn Material>>, prototypes: &HashMap<String, Prototype>, group: &mut Group) -> Result<(), SceneError> {
+    let invalid = |message: String| SceneError::Invalid(path.to_path_buf(), message);
+    let material = |name: &String| -> Result<Arc<dyn Material>, SceneError> {
+        materials.get(name).cloned().ok_or(SceneError::UnknownMaterial(path.to_path_buf(), name.clone()))
+    };
+
+    match object {
+        ObjectFile::Sphere { center, radius, material: name } => {
+            if radius == 0.0 {
+                return Err(invalid("sphere radius must not be zero".to_string()));
+            }
+            let material: Arc<dyn Material> = material(&name)?;
+            group.add(Sphere{center: vector(center), radius, material: material.clone()}, material.emissive());
+        }
+        ObjectFile::MovingSphere { center, radius, material: name } => {
+            if radius == 0.0 {
+                return Err(invalid("sphere radius must not be zero".to_string()));
+            }
+            //MOVING SURFACES CAN ONLY BE FOUND BY HITTING THEM, NOT SAMPLED AS LIGHTS
+            group.add(MovingSphere{center: center.map(vector), radius, material: material(&name)?}, false);
+        }
+        ObjectFile::Triangle { vertices, material: name } => {
+            let material: Arc<dyn Material> = material(&name)?;
+            group.add(Triangle{vertices: vertices.map(vector), material: material.clone()}, material.emissive());
+        }
+        ObjectFile::Plane { point, normal, material: name } => {
+            if vector(normal).length_squared() == 0.0 {
+                return Err(invalid("plane normal must not be zero".to_string()));
+            }
+            //UNBOUNDED SURFACES CAN ONLY BE FOUND BY HITTING THEM, NOT SAMPLED AS LIGHTS
+            group.add(Plane{point: vector(point), normal: vector(normal).unit_vector(), material: material(&name)?}, false);
+        }
+        ObjectFile::Quad { corner, u, v, material: name } => {
+            if Vector3::cross(&vector(u), &vector(v)).length_squared() == 0.0 {
+                return Err(invalid("quad edges must not be parallel or zero".to_string()));
+            }
+            let material: Arc<dyn Material> = material(&name)?;
+            group.add(Quad{corner: vector(corner), u: vector(u), v: vector(v), material: material.clone()}, material.emissive());
+        }
+        ObjectFile::Disk { center, normal, radius, material: name } => {
+            if vector(normal).length_squared() == 0.0 || radius <= 0.0 {
+                return Err(invalid("disk needs a non zero normal and a positive radius".to_string()));
+            }
+            let material: Arc<dyn Material> = material(&name)?;
+            group.add(Disk{center: vector(center), normal: vector(normal), radius, material: material.clone()}, material.emissive());
+        }
+        ObjectFile::Cuboid { min, max, material: name } => {
+            let (min, max): (Vector3, Vector3) = (Vector3::min(&vector(min), &vector(max)), Vector3::max(&vector(min), &vector(max)));
+            let size: Vector3 = max - min;
+            if size.x == 0.0 || size.y == 0.0 || size.z == 0.0 {
+                return Err(invalid("box must not be flat".to_string()));
+            }
+            //ONLY SPHERES, TRIANGLES, QUADS AND DISKS CAN BE SAMPLED AS LIGHTS
+            group.add(Cuboid{min, max, material: material(&name)?}, false);
+        }
+        ObjectFile::Cylinder { base, top, radius, material: name } => {
+            if base == top || radius <= 0.0 {
+                return Err(invalid("cylinder needs distinct base and top and a positive radius".to_string()));
+            }
+            group.add(Cylinder{base: vector(base), top: vector(top), radius, material: material(&name)?}, false);
+        }
+        ObjectFile::Cone { base, apex, radius, material: name } => {
+            if base == apex || radius <= 0.0 {
+                return Err(invalid("cone needs distinct base and apex and a positive radius".to_string()));
+            }
+            group.add(Cone{base: vector(base), apex: vector(apex), radius, material: material(&name)?}, false);
+        }
+        ObjectFile::Torus { center, normal, major_radius, minor_radius, material: name } => {
+            if vector(normal).length_squared() == 0.0 || minor_radius <= 0.0 || major_radius < minor_radius {
+                return Err(invalid("torus needs a non zero normal and 0 < minor_radius <= major_radius".to_string()));
+            }
+            group.add(Torus{center: vector(center), normal: vector(normal), major_radius, minor_radius, material: material(&name)?}, false);
+        }
+        ObjectFile::Mesh { positions, indices, normals, uvs, material: name } => {
+            if let Some(index) = indices.iter().flatten().find(|index: &&usize| **index >= positions.len()) {
+                return Err(invalid(format!("mesh index {} is out of range for {} positions", index, positions.len())));
+            }
+            if !normals.is_empty() && normals.len() != positions.len() {
+                return Err(invalid(format!("mesh has {} normals for {} positions", normals.len(), positions.len())));
+            }
+            if let Some(index) = normals.iter().position(|normal: &[f32; 3]| vector(*normal).length_squared() == 0.0) {
+                return Err(invalid(format!("mesh normal {} must not be zero", index)));
+            }
+            if !uvs.is_empty() && uvs.len() != positions.len() {
+                return Err(invalid(format!("mesh has {} uvs for {} positions", uvs.len(), positions.len())));
+            }
+            let material: Arc<dyn Material> = material(&name)?;
+            let materials: Vec<Arc<dyn Material>> = vec![material; indices.len()];
+            let mesh: Arc<TriangleMesh> = Arc::new(TriangleMesh::new(
+                positions.into_iter().map(vector).collect(),
+                normals.into_iter().map(|n: [f32; 3]| vector(n).unit_vector()).collect(),
+                uvs.into_iter().map(|uv: [f32; 2]| (uv[0], uv[1])).collect(),
+                indices,
+                materials,
+            ));
+            group.add_mesh(&mesh);
+        }
+        ObjectFile::Obj { path: obj, material: name } => {
+            let obj: PathBuf = path.parent().unwrap_or(Path::new("")).join(obj);
+            let mesh: Arc<TriangleMesh> = Arc::new(load_obj(&obj, material(&name)?)?);
+            group.add_mesh(&mesh);
+        }
+        ObjectFile::ConstantMedium { boundary, density, material: name } => {
+            if density <= 0.0 {
+                return Err(invalid(format!("constant_medium density {} must be positive", density)));
+            }
+            let mut inside: Group = group.nested();
+            add_object(path, *boundary, materials, prototypes, &mut inside)?;
+            group.instances = inside.instances;
+            let boundary: Arc<dyn Surface> = Arc::new(BvhNode::new(inside.world));
+            if !boundary.is_closed() {
+                return Err(invalid("constant_medium boundary must be closed, planes, quads, disks, triangles, open meshes and media are not".to_string()));
+            }
+            group.add(ConstantMedium{boundary, medium: Medium { density, phase: material(&name)? }}, false);
+        }
+        ObjectFile::Csg { operation, left, right } => {
+            let operation: Operation = match operation {
+                OperationFile::Union => Operation::Union,
+                OperationFile::Intersection => Operation::Intersection,
+                OperationFile::Difference => Operation::Difference,
+            };
+            let mut left_group: Group = group.nested();
+            add_object(path, *left, materials, prototypes, &mut left_group)?;
+            let mut right_group: Group = left_group.nested();
+            add_object(path, *right, materials, prototypes, &mut right_group)?;
+            group.instances = right_group.instances;
+            let left: Arc<dyn Surface> = Arc::new(BvhNode::new(left_group.world));
+            let right: Arc<dyn Surface> = Arc::new(BvhNode::new(right_group.world));
+            if !left.is_closed() || !right.is_closed() {
+                return Err(invalid("csg operands must be closed, planes, quads, disks, triangles, open meshes and media are not".to_string()));
+            }
+            //ONLY SPHERES, TRIANGLES, QUADS AND DISKS CAN BE SAMPLED AS LIGHTS
+            group.add(Csg{operation, left, right}, false);
+        }
+        ObjectFile::Instance { prototype: name, transform, motion } => {
+            let prototype: &Prototype = prototypes.get(&name).ok_or(SceneError::UnknownPrototype(path.to_path_buf(), name.clone()))?;
+            let start: Vec<Step> = steps(path, transform)?;
+            let transform: Transform = Step::compose(&start).ok_or(invalid("transform is singular".to_string()))?;
+            if motion.is_empty() {
+                let instance: usize = group.next_instance();
+                group.world.add(Transformed{surface: prototype.surface.clone(), transform, instance});
+                for light in prototype.lights.iter() {
+                    group.lights.surfaces.push(Arc::new(Transformed{surface: light.clone(), transform, instance}));
+                }
+            } else {
+                let end: Vec<Step> = steps(path, motion)?;
+                Step::compose(&end).ok_or(invalid("motion is singular".to_string()))?;
+                let motion: Motion = Motion::new(&start, &end).ok_or(invalid(
+                    "motion must match transform step by step, keeping rotation axes, matrices and the signs of scales".to_string()))?;
+                //MOVING SURFACES CAN ONLY BE FOUND BY HITTING THEM, NOT SAMPLED AS LIGHTS
+                let instance: usize = group.next_instance();
+                group.world.add(Animated{surface: prototype.surface.clone(), motion, instance});
+            }
+        }
+    }
+    Ok(())
+}
+
+fn load_camera(path: &Path, camera: CameraFile, aspect_ratio: f32) -> Result<Box<dyn Camera>, SceneError> {
+    let invalid = |message: String| SceneError::Invalid(path.to_path_buf(), message);
+    let name: &str = match camera.projection {
+        ProjectionFile::Perspective => "perspective",
+        ProjectionFile::Orthographic { .. } => "orthographic",
+        ProjectionFile::Fisheye { .. } => "fisheye",
+        ProjectionFile::Equirectangular => "equirectangular",
+        ProjectionFile::Cylindrical => "cylindrical",
+    };
+    let vertical_fov = || -> Result<f32, SceneError> {
+        match camera.vertical_fov {
+            None => Err(invalid(format!("camera vertical_fov is required for {} projections", name))),
+            Some(vertical_fov) if !(vertical_fov > 0.0 && vertical_fov < 180.0) => {
+                Err(invalid(format!("camera vertical_fov {} must be between 0 and 180", vertical_fov)))
+            }
+            Some(vertical_fov) => Ok(vertical_fov),
+        }
+    };
+    if camera.vertical_fov.is_some() && !matches!(camera.projection, ProjectionFile::Perspective | ProjectionFile::Cylindrical) {
+        return Err(invalid(format!("camera vertical_fov does not apply to {} projections", name)));
+    }
+    if camera.aperture < 0.0 {
+        return Err(invalid(format!("camera aperture {} must not be negative", camera.aperture)));
+    }
+    if (camera.aperture > 0.0 || camera.focus_distance.is_some()) && !matches!(camera.projection, ProjectionFile::Perspective) {
+        return Err(invalid(format!("camera aperture and focus_distance do not apply to {} projections", name)));
+    }
+    let origin: Point3 = vector(camera.origin);
+    let target: Point3 = vector(camera.target);
+    if (origin - target).length_squared() == 0.0 {
+        return Err(invalid("camera origin and target must differ".to_string()));
+    }
+    let up: Vector3 = vector(camera.up);
+    if Vector3::cross(&up, &(origin - target)).length() <= f32::EPSILON * up.length() * (origin - target).length() {
+        return Err(invalid("camera up must not be zero or parallel to the view direction".to_string()));
+    }
+    if !camera.shutter.iter().all(|time: &f32| (0.0..=1.0).contains(time)) {
+        return Err(invalid(format!("camera shutter times {:?} must lie between 0 and 1", camera.shutter)));
+    }
+    if camera.shutter[0] > camera.shutter[1] {
+        return Err(invalid(format!("camera shutter closes at {} before opening at {}", camera.shutter[1], camera.shutter[0])));
+    }
+    let [open, close] = camera.shutter;
+    let shutter: Shutter = Shutter { open, close };
+    let frame: Frame = look_at(origin, target, &up);
+
+    Ok(match camera.projection {
+        ProjectionFile::Perspective => {
+            let focus_distance: f32 = camera.focus_distance.unwrap_or((origin - target).length());
+            Box::new(Perspective::new(frame, vertical_fov()?, aspect_ratio, camera.aperture, focus_distance, shutter))
+        }
+        ProjectionFile::Orthographic { height } => {
+            if height <= 0.0 {
+                return Err(invalid(format!("camera height {} must be positive", height)));
+            }
+            Box::new(Orthographic::new(frame, height, aspect_ratio, shutter))
+        }
+        ProjectionFile::Fisheye { fov, mapping } => {
+            if !(fov > 0.0 && fov <= 360.0) {
+                return Err(invalid(format!("camera fov {} must be between 0 and 360", fov)));
+            }
+            let mapping: Mapping = match mapping {
+                MappingFile::Equidistant => Mapping::Equidistant,
+                MappingFile::Equisolid => Mapping::Equisolid,
+            };
+            Box::new(Fisheye::new(frame, mapping, fov, aspect_ratio, shutter))
+        }
+        ProjectionFile::Equirectangular => Box::new(Equirectangular { frame, shutter }),
+        ProjectionFile::Cylindrical => Box::new(Cylindrical::new(frame, vertical_fov()?, shutter)),
+    })
+}
+
+fn add_light(path: &Path, light: LightFile, group: &mut Group) -> Result<(), SceneError> {
+    let invalid = |message: String| SceneError::Invalid(path.to_path_buf(), message);
+    let emission = |colour: [f32; 3], intensity: f32| -> Result<Vector3, SceneError> {
+        if intensity < 0.0 || colour.iter().any(|channel: &f32| *channel < 0.0) {
+            return Err(invalid("light colour and intensity must not be negative".to_string()));
+        }
+        Ok(intensity * vector(colour))
+    };
+
+    match light {
+        LightFile::Point { position, colour, intensity } => {
+            group.lights.punctual.push(Box::new(Point{position: vector(position), intensity: emission(colour, intensity)?}));
+        }
+        LightFile::Spot { position, target, colour, intensity, cone_angle, cone_delta, profile } => {
+            let direction: Vector3 = vector(target) - vector(position);
+            if direction.length_squared() == 0.0 {
+                return Err(invalid("spot position and target must differ".to_string()));
+            }
+            let profile: Option<Profile> = match profile {
+                Some(profile) => Some(load_ies(&path.parent().unwrap_or(Path::new("")).join(profile))?),
+                None => None,
+            };
+            //A PROFILE WITHOUT A CONE LIGHTS EVERY DIRECTION IT COVERS
+            let (falloff, cutoff): (f32, f32) = match cone_angle {
+                None if profile.is_some() => (180.0, 180.0),
+                cone_angle => {
+                    let cone_angle: f32 = cone_angle.unwrap_or(default_cone_angle());
+                    if !(cone_angle > 0.0 && cone_angle <= 180.0) {
+                        return Err(invalid(format!("spot cone_angle {} must be above 0 and at most 180", cone_angle)));
+                    }
+                    if !(0.0..=cone_angle).contains(&cone_delta) {
+                        return Err(invalid(format!("spot cone_delta {} must be between 0 and cone_angle", cone_delta)));
+                    }
+                    (cone_angle - cone_delta, cone_angle)
+                }
+            };
+            let spot: Spot = Spot::new(vector(position), &direction, emission(colour, intensity)?, falloff.to_radians(), cutoff.to_radians(), profile);
+            group.lights.punctual.push(Box::new(spot));
+        }
+        LightFile::Directional { direction, colour, intensity } => {
+            if vector(direction).length_squared() == 0.0 {
+                return Err(invalid("directional light direction must not be zero".to_string()));
+            }
+            group.lights.punctual.push(Box::new(Directional{direction: vector(direction).unit_vector(), irradiance: emission(colour, intensity)?}));
+        }
+        //AREA LIGHTS ARE EMISSIVE SURFACES, SEEN BY THE CAMERA AND SAMPLED FOR DIRECT LIGHTING
+        LightFile::Sphere { center, radius, colour, intensity } => {
+            if radius <= 0.0 {
+                return Err(invalid("sphere light radius must be positive".to_string()));
+            }
+            let material: Arc<dyn Material> = Arc::new(DiffuseLight{emit: emission(colour, intensity)?});
+            group.add(Sphere{center: vector(center), radius, material}, true);
+        }
+        LightFile::Quad { corner, u, v, colour, intensity } => {
+            if Vector3::cross(&vector(u), &vector(v)).length_squared() == 0.0 {
+                return Err(invalid("quad light edges must not be parallel or zero".to_string()));
+            }
+            let material: Arc<dyn Material> = Arc::new(DiffuseLight{emit: emission(colour, intensity)?});
+            group.add(Quad{corner: vector(corner), u: vector(u), v: vector(v), material}, true);
+        }
+        LightFile::Disk { center, normal, radius, colour, intensity } => {
+            if vector(normal).length_squared() == 0.0 || radius <= 0.0 {
+                return Err(invalid("disk light needs a non zero normal and a positive radius".to_string()));
+            }
+            let material: Arc<dyn Material> = Arc::new(DiffuseLight{emit: emission(colour, intensity)?});
+            group.add(Disk{center: vector(center), normal: vector(normal), radius, material}, true);
+        }
+    }
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "[render]\nwidth = 4\nheight = 4\nsamples = 1\ndepth = 2\n\n[camera]\norigin = [0, 0, 5]\ntarget = [0, 0, 0]\nvertical_fov = 40\n";
+
+    const GREY: &str = "[materials.grey]\ntype = \"lambertian\"\nalbedo = [0.5, 0.5, 0.5]\n";
+
+    //WRITES THE SCENE TO A FILE OF ITS OWN AND LOADS IT
+    fn load(name: &str, source: &str) -> Result<Scene, SceneError> {
+        let directory: PathBuf = std::env::temp_dir().join(format!("raytracer-scene-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path: PathBuf = directory.join(name);
+        std::fs::write(&path, source).unwrap();
+        load_scene(&path)
+    }
+
+    fn invalid(result: Result<Scene, SceneError>, fragment: &str) {
+        match result {
+            Err(SceneError::Invalid(_, message)) => assert!(message.contains(fragment), "'{}' does not mention '{}'", message, fragment),
+            Err(error) => panic!("expected an invalid scene, got {}", error),
+            Ok(_) => panic!("expected an invalid scene, it loaded"),
+        }
+    }
+
+    #[test]
+    fn a_minimal_scene_loads() {
+        let source: String = format!("{}{}[[objects]]\ntype = \"sphere\"\ncenter = [0, 0, 0]\nradius = 1\nmaterial = \"grey\"\n", HEADER, GREY);
+        assert!(load("minimal.toml", &source).is_ok());
+    }
+
+    #[test]
+    fn unknown_materials_are_named() {
+        let source: String = format!("{}{}[[objects]]\ntype = \"sphere\"\ncenter = [0, 0, 0]\nradius = 1\nmaterial = \"gold\"\n", HEADER, GREY);
+        assert!(matches!(load("material.toml", &source), Err(SceneError::UnknownMaterial(_, ref name)) if name == "gold"));
+    }
+
+    #[test]
+    fn prototype_cycles_are_rejected() {
+        let source: String = format!(
+            "{}[[prototypes.a.objects]]\ntype = \"instance\"\nprototype = \"b\"\n\n[[prototypes.b.objects]]\ntype = \"instance\"\nprototype = \"a\"\n",
+            HEADER,
+        );
+        invalid(load("cycle.toml", &source), "prototypes 'a', 'b' form a reference cycle");
+        let source: String = format!("{}[[prototypes.a.objects]]\ntype = \"instance\"\nprototype = \"a\"\n", HEADER);
+        invalid(load("self.toml", &source), "prototypes 'a' form a reference cycle");
+        let source: String = format!("{}[[prototypes.a.objects]]\ntype = \"instance\"\nprototype = \"c\"\n", HEADER);
+        assert!(matches!(load("prototype.toml", &source), Err(SceneError::UnknownPrototype(_, ref name)) if name == "c"));
+    }
+
+    #[test]
+    fn bad_up_vectors_are_rejected() {
+        invalid(load("zero_up.toml", &format!("{}up = [0, 0, 0]\n", HEADER)), "camera up");
+        invalid(load("parallel_up.toml", &format!("{}up = [0, 0, -2]\n", HEADER)), "camera up");
+    }
+
+    #[test]
+    fn zero_mesh_normals_are_rejected() {
+        let source: String = format!(
+            "{}{}[[objects]]\ntype = \"mesh\"\npositions = [[0, 0, 0], [1, 0, 0], [0, 1, 0]]\nnormals = [[0, 0, 1], [0, 0, 0], [0, 0, 1]]\nindices = [[0, 1, 2]]\nmaterial = \"grey\"\n",
+            HEADER, GREY,
+        );
+        invalid(load("normals.toml", &source), "mesh normal 1 must not be zero");
+    }
+}
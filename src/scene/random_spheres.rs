@@ -1,16 +1,22 @@
 use std::f32::consts::PI;
-use std::rc::Rc;
+use std::sync::Arc;
 use xorshift::Rng;
 
 use crate::colour;
 use crate::hit::HitList;
+use crate::camera::{Shutter, look_at};
+use crate::camera::perspective::Perspective;
+use crate::light::Lights;
+use crate::scene::{Scene, Settings};
+use crate::background::gradient::Gradient;
 use crate::Sphere;
 use crate::Material;
 use crate::{Lambertian, Metallic, Dielectric};
 use crate::{Vector3, vector_3::Point3};
 use crate::colour::Colour;
+use crate::texture::solid_colour::SolidColour;
 
-pub fn random_spheres_scene(rng: &mut xorshift::StdRng) -> HitList {
+pub fn random_spheres_scene(rng: &mut xorshift::StdRng) -> Scene {
 
     struct CollisionSphere {
         center: Point3,
@@ -21,18 +27,18 @@ pub fn random_spheres_scene(rng: &mut xorshift::StdRng) -> HitList {
     let mut spheres: Vec<CollisionSphere> = vec![];
     let radius: f32 = 1.0;
 
-    let material: Rc::<dyn Material> = Rc::new(Lambertian{albedo: 0.5 * Colour::ONE});
+    let material: Arc::<dyn Material> = Arc::new(Lambertian{albedo: Arc::new(SolidColour{colour: 0.5 * Colour::ONE})});
     world.add(Sphere{center: V3!(0.0, -1000.0, 0.0), radius: 1000.0, material});
 
-    let material: Rc::<dyn Material> = Rc::new(Dielectric{refraction_index: 1.5});
+    let material: Arc::<dyn Material> = Arc::new(Dielectric::new(1.5));
     world.add(Sphere{center: V3!(0.0, 1.0, 0.0), radius, material});
     spheres.push(CollisionSphere{center: V3!(0.0, 1.0, 0.0), radius });
     
-    let material: Rc::<dyn Material> = Rc::new(Lambertian{albedo: V3!(0.4, 0.2, 0.1)});
+    let material: Arc::<dyn Material> = Arc::new(Lambertian{albedo: Arc::new(SolidColour{colour: V3!(0.4, 0.2, 0.1)})});
     world.add(Sphere{center: V3!(-4.0, 1.0, 0.0), radius, material});
     spheres.push(CollisionSphere{center: V3!(-4.0, 1.0, 0.0), radius });
 
-    let material: Rc::<dyn Material> = Rc::new(Metallic{albedo: V3!(0.7, 0.6, 0.5), fuzziness: 0.0});
+    let material: Arc::<dyn Material> = Arc::new(Metallic{albedo: Arc::new(SolidColour{colour: V3!(0.7, 0.6, 0.5)}), fuzziness: 0.0});
     world.add(Sphere{center: V3!(4.0, 1.0, 0.0), radius, material});
     spheres.push(CollisionSphere{center: V3!(4.0, 1.0, 0.0), radius });
 
@@ -52,7 +58,7 @@ pub fn random_spheres_scene(rng: &mut xorshift::StdRng) -> HitList {
                     Vector3::length(&(a.center - b.center)) < a.radius + b.radius + 0.05
                 }
                 
-                if spheres.iter().all(|b: &CollisionSphere| !sphere_intersection(&CollisionSphere {center, radius}, &b)) {
+                if spheres.iter().all(|b: &CollisionSphere| !sphere_intersection(&CollisionSphere {center, radius}, b)) {
                     break;
                 };
             }
@@ -61,24 +67,42 @@ pub fn random_spheres_scene(rng: &mut xorshift::StdRng) -> HitList {
                 if choose_mat < 0.8 {
                     // diffuse
                     let hsv: Colour = Colour{x: rng.next_f32() * 2.0 * PI, y: 1.0, z: 0.8};
-                    let albedo: Colour = colour::hsv2rgb(&hsv);
-                    let material: Rc::<dyn Material> = Rc::new(Lambertian{albedo});
+                    let colour: Colour = colour::hsv2rgb(&hsv);
+                    let material: Arc::<dyn Material> = Arc::new(Lambertian{albedo: Arc::new(SolidColour{colour})});
                     world.add(Sphere{center, radius: 0.2, material});
                 } else if choose_mat < 0.95 {
                     // metal
                     let hsv: Colour = Colour{x: rng.next_f32() * 2.0 * PI, y: 0.2, z: 0.8};
-                    let albedo: Colour = colour::hsv2rgb(&hsv);
+                    let colour: Colour = colour::hsv2rgb(&hsv);
                     let fuzziness: f32 = rng.next_f32() / 2.0;
-                    let material: Rc::<dyn Material> = Rc::new(Metallic{albedo, fuzziness});
+                    let material: Arc::<dyn Material> = Arc::new(Metallic{albedo: Arc::new(SolidColour{colour}), fuzziness});
                     world.add(Sphere{center, radius: 0.2, material});
                 } else {
                     // glass
-                    let material: Rc::<dyn Material> = Rc::new(Dielectric{refraction_index: 1.5});
+                    let material: Arc::<dyn Material> = Arc::new(Dielectric::new(1.5));
                     world.add(Sphere{center, radius: 0.2, material});
                 }
             }
         }
     }
 
-    return world;
+    //IMAGE
+    const ASPECT_RATIO: f32 = 3.0 / 2.0;
+    const WIDTH : u16 = 1200;
+    const HEIGHT: u16 = (WIDTH as f32 / ASPECT_RATIO) as u16;
+    let settings: Settings = Settings { width: WIDTH, height: HEIGHT, samples: 64, depth: 16 };
+
+    //CAMERA
+    let origin: Vector3 = V3!(13.0, 2.0, 3.0);
+    let target: Vector3 = V3!(0.0, 0.0, 0.0);
+    let camera: Perspective = Perspective::new(
+        look_at(origin, target, &Vector3::UP),
+        20.0,
+        ASPECT_RATIO,
+        0.1,
+        10.0,
+        Shutter::default(),
+        );
+
+    Scene { world, camera: Box::new(camera), settings, background: Arc::new(Gradient), lights: Lights::default(), medium: None }
 }
\ No newline at end of file
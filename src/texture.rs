@@ -0,0 +1,23 @@
+use crate::colour::Colour;
+use crate::hit::Hit;
+use crate::vector_3::Point3;
+
+pub mod solid_colour;
+pub mod checker;
+pub mod noise;
+pub mod image_texture;
+pub mod variation;
+
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f32, v: f32, point: &Point3) -> Colour;
+
+    //value AT A HIT, FOR TEXTURES THAT FILTER OVER THE PIXEL FOOTPRINT OR DEPEND ON WHAT WAS HIT
+    fn value_at(&self, hit: &Hit) -> Colour {
+        self.value(hit.uv.0, hit.uv.1, &hit.point)
+    }
+}
+
+//TEXTURE EVALUATED WHERE THE RAY HIT
+pub fn at(texture: &dyn Texture, hit: &Hit) -> Colour {
+    texture.value_at(hit)
+}
@@ -0,0 +1,35 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::colour::Colour;
+use crate::hit::Hit;
+use crate::texture::Texture;
+use crate::vector_3::Point3;
+
+//SOLID CHECKERBOARD IN WORLD SPACE, size IS THE EDGE OF A CELL
+pub struct Checker {
+    pub odd: Arc<dyn Texture>,
+    pub even: Arc<dyn Texture>,
+    pub size: f32,
+}
+
+impl Checker {
+    fn is_odd(&self, point: &Point3) -> bool {
+        let frequency: f32 = PI / self.size;
+        f32::sin(frequency * point.x) * f32::sin(frequency * point.y) * f32::sin(frequency * point.z) < 0.0
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f32, v: f32, point: &Point3) -> Colour {
+        if self.is_odd(point) {self.odd.value(u, v, point)} else {self.even.value(u, v, point)}
+    }
+
+    //CELLS SMALLER THAN THE PIXEL FOOTPRINT FADE INTO THE AVERAGE OF BOTH SIDES INSTEAD OF ALIASING
+    fn value_at(&self, hit: &Hit) -> Colour {
+        let (odd, even): (Colour, Colour) = (self.odd.value_at(hit), self.even.value_at(hit));
+        let sharp: Colour = if self.is_odd(&hit.point) {odd} else {even};
+        let blur: f32 = (2.0 * hit.footprint_width() / self.size - 1.0).clamp(0.0, 1.0);
+        (1.0 - blur) * sharp + blur * 0.5 * (odd + even)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::colour::Colour;
+use crate::render::Framebuffer;
+use crate::texture::Texture;
+use crate::vector_3::Point3;
+
+//WHAT HAPPENS TO UVS OUTSIDE [0, 1]
+#[derive(Copy, Clone)]
+pub enum Wrap {
+    Repeat,
+    Mirror,
+    Clamp,
+}
+
+impl Wrap {
+    fn texel(&self, index: i64, size: i64) -> i64 {
+        match self {
+            Wrap::Repeat => index.rem_euclid(size),
+            Wrap::Mirror => {
+                let index: i64 = index.rem_euclid(2 * size);
+                if index < size {index} else {2 * size - 1 - index}
+            }
+            Wrap::Clamp => index.clamp(0, size - 1),
+        }
+    }
+}
+
+//V GOES UP THE IMAGE, LIKE THE FRAMEBUFFER ROWS
+pub struct ImageTexture {
+    pub image: Framebuffer,
+    pub wrap: Wrap,
+}
+
+impl ImageTexture {
+    fn texel(&self, i: i64, j: i64) -> Colour {
+        let i: i64 = self.wrap.texel(i, self.image.width as i64);
+        let j: i64 = self.wrap.texel(j, self.image.height as i64);
+        self.image.get(i as u16, j as u16)
+    }
+}
+
+impl Texture for ImageTexture {
+    //BILINEAR BETWEEN THE FOUR NEAREST TEXEL CENTRES
+    fn value(&self, u: f32, v: f32, _: &Point3) -> Colour {
+        let x: f32 = u * self.image.width as f32 - 0.5;
+        let y: f32 = v * self.image.height as f32 - 0.5;
+        let (i, j): (f32, f32) = (x.floor(), y.floor());
+        let (s, t): (f32, f32) = (x - i, y - j);
+        let (i, j): (i64, i64) = (i as i64, j as i64);
+
+        let bottom: Colour = (1.0 - s) * self.texel(i, j) + s * self.texel(i + 1, j);
+        let top: Colour = (1.0 - s) * self.texel(i, j + 1) + s * self.texel(i + 1, j + 1);
+        (1.0 - t) * bottom + t * top
+    }
+}
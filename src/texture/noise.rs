@@ -0,0 +1,106 @@
+use xorshift::Rng;
+
+use crate::colour::Colour;
+use crate::hit::Hit;
+use crate::texture::Texture;
+use crate::vector_3::{Vector3, Point3};
+
+const POINT_COUNT: usize = 256;
+
+//GRADIENT NOISE OVER A LATTICE OF RANDOM UNIT VECTORS, IN [-1, 1]
+pub struct Perlin {
+    gradients: Vec<Vector3>,
+    permutations: [Vec<usize>; 3],
+}
+
+fn permutation(rng: &mut xorshift::StdRng) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..POINT_COUNT).collect();
+    for i in (1..POINT_COUNT).rev() {
+        let target: usize = rng.gen_range(0, i + 1);
+        permutation.swap(i, target);
+    }
+    permutation
+}
+
+impl Perlin {
+    pub fn new(rng: &mut xorshift::StdRng) -> Perlin {
+        let gradients: Vec<Vector3> = (0..POINT_COUNT).map(|_| Vector3::random_unit_vector(rng)).collect();
+        let permutations: [Vec<usize>; 3] = [permutation(rng), permutation(rng), permutation(rng)];
+        Perlin { gradients, permutations }
+    }
+
+    pub fn noise(&self, point: &Point3) -> f32 {
+        let floor: [f32; 3] = [point.x.floor(), point.y.floor(), point.z.floor()];
+        let fraction: [f32; 3] = [point.x - floor[0], point.y - floor[1], point.z - floor[2]];
+        //HERMITE SMOOTHING HIDES THE LATTICE
+        let [u, v, w] = fraction.map(|t: f32| t * t * (3.0 - 2.0 * t));
+
+        let mut accumulated: f32 = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let corner: [usize; 3] = [di, dj, dk];
+                    let index: usize = (0..3)
+                        .map(|axis: usize| self.permutations[axis][(floor[axis] as i64 + corner[axis] as i64).rem_euclid(POINT_COUNT as i64) as usize])
+                        .fold(0, |hash: usize, value: usize| hash ^ value);
+                    let offset: Vector3 = V3!(fraction[0] - di as f32, fraction[1] - dj as f32, fraction[2] - dk as f32);
+                    let weight: f32 = (if di == 1 {u} else {1.0 - u}) * (if dj == 1 {v} else {1.0 - v}) * (if dk == 1 {w} else {1.0 - w});
+                    accumulated += weight * Vector3::dot(&self.gradients[index], &offset);
+                }
+            }
+        }
+        accumulated
+    }
+
+    //SUM OF octaves LAYERS, EACH TWICE THE FREQUENCY AND HALF THE WEIGHT OF THE PREVIOUS ONE
+    pub fn turbulence(&self, point: &Point3, octaves: u32) -> f32 {
+        let mut accumulated: f32 = 0.0;
+        let mut point: Point3 = *point;
+        let mut weight: f32 = 1.0;
+        for _ in 0..octaves {
+            accumulated += weight * self.noise(&point).abs();
+            point = 2.0 * point;
+            weight *= 0.5;
+        }
+        accumulated
+    }
+}
+
+//PLAIN NOISE WITH ONE OCTAVE, TURBULENCE WITH MORE
+pub struct Noise {
+    pub perlin: Perlin,
+    pub colour: Colour,
+    pub scale: f32,
+    pub octaves: u32,
+}
+
+impl Noise {
+    fn intensity(&self, point: &Point3, octaves: u32) -> f32 {
+        let point: Point3 = self.scale * *point;
+        let intensity: f32 = if self.octaves <= 1 {
+            0.5 * (1.0 + self.perlin.noise(&point))
+        } else {
+            self.perlin.turbulence(&point, octaves)
+        };
+        intensity.clamp(0.0, 1.0)
+    }
+}
+
+impl Texture for Noise {
+    fn value(&self, _: f32, _: f32, point: &Point3) -> Colour {
+        self.intensity(point, self.octaves) * self.colour
+    }
+
+    //OCTAVES WHOSE LATTICE IS FINER THAN THE PIXEL FOOTPRINT ONLY ADD ALIASING, SO THEY ARE LEFT OUT
+    fn value_at(&self, hit: &Hit) -> Colour {
+        let width: f32 = self.scale * hit.footprint_width();
+        if width <= 0.0 {
+            return self.value(hit.uv.0, hit.uv.1, &hit.point);
+        }
+        if self.octaves <= 1 && width >= 1.0 {
+            return 0.5 * self.colour;
+        }
+        let resolved: u32 = f32::max(0.0, -width.log2()).ceil() as u32;
+        self.intensity(&hit.point, resolved.clamp(1, self.octaves.max(1))) * self.colour
+    }
+}
@@ -0,0 +1,13 @@
+use crate::colour::Colour;
+use crate::texture::Texture;
+use crate::vector_3::Point3;
+
+pub struct SolidColour {
+    pub colour: Colour
+}
+
+impl Texture for SolidColour {
+    fn value(&self, _: f32, _: f32, _: &Point3) -> Colour {
+        self.colour
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use crate::colour::Colour;
+use crate::hit::Hit;
+use crate::texture::Texture;
+use crate::vector_3::Point3;
+
+#[derive(Copy, Clone)]
+pub enum Key {
+    Instance,
+    Primitive,
+}
+
+//ONE OF textures PER INSTANCE OR PER MESH TRIANGLE, SO COPIES OF A PROTOTYPE OR FACES OF A MESH CAN DIFFER
+pub struct Variation {
+    pub textures: Vec<Arc<dyn Texture>>,
+    pub key: Key,
+}
+
+//SPLITMIX64 FINALISER, SO NEIGHBOURING IDS DO NOT WALK THROUGH THE LIST IN ORDER
+fn mix(value: u64) -> u64 {
+    let value: u64 = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    let value: u64 = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+    value ^ (value >> 31)
+}
+
+impl Texture for Variation {
+    //WITHOUT A HIT THERE IS NOTHING TO PICK BY
+    fn value(&self, u: f32, v: f32, point: &Point3) -> Colour {
+        self.textures[0].value(u, v, point)
+    }
+
+    fn value_at(&self, hit: &Hit) -> Colour {
+        let id: usize = match self.key {
+            Key::Instance => hit.instance.unwrap_or(0),
+            Key::Primitive => hit.primitive,
+        };
+        self.textures[(mix(id as u64) % self.textures.len() as u64) as usize].value_at(hit)
+    }
+}
@@ -0,0 +1,206 @@
+use crate::aabb::Aabb;
+use crate::matrix_4::Matrix4;
+use crate::vector_3::{Vector3, Point3};
+
+//AN AFFINE MAP FROM OBJECT TO WORLD SPACE, CARRYING ITS INVERSE
+#[derive(Debug, Copy, Clone)]
+pub struct Transform {
+    pub matrix: Matrix4,
+    pub inverse: Matrix4,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { matrix: Matrix4::IDENTITY, inverse: Matrix4::IDENTITY };
+
+    //NONE FOR SINGULAR MATRICES
+    pub fn new(matrix: Matrix4) -> Option<Transform> {
+        Some(Transform { matrix, inverse: matrix.inverse()? })
+    }
+
+    pub fn translation(offset: &Vector3) -> Transform {
+        Transform { matrix: Matrix4::translation(offset), inverse: Matrix4::translation(&-*offset) }
+    }
+
+    //NONE IF ANY FACTOR IS ZERO
+    pub fn scaling(factors: &Vector3) -> Option<Transform> {
+        if factors.x * factors.y * factors.z == 0.0 {
+            return None;
+        }
+        Some(Transform { matrix: Matrix4::scaling(factors), inverse: Matrix4::scaling(&V3!(1.0 / factors.x, 1.0 / factors.y, 1.0 / factors.z)) })
+    }
+
+    pub fn rotation(axis: &Vector3, degrees: f32) -> Transform {
+        let matrix: Matrix4 = Matrix4::rotation(axis, degrees);
+        Transform { matrix, inverse: matrix.transpose() }
+    }
+
+    //self FIRST, THEN next
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform { matrix: next.matrix * self.matrix, inverse: self.inverse * next.inverse }
+    }
+
+    pub fn point(&self, p: &Point3) -> Point3 {
+        self.matrix.point(p)
+    }
+
+    pub fn vector(&self, v: &Vector3) -> Vector3 {
+        self.matrix.vector(v)
+    }
+
+    //NORMALS TRANSFORM BY THE INVERSE TRANSPOSE, THE RESULT IS NOT UNIT LENGTH
+    pub fn normal(&self, n: &Vector3) -> Vector3 {
+        self.inverse.transpose().vector(n)
+    }
+
+    pub fn inverse_point(&self, p: &Point3) -> Point3 {
+        self.inverse.point(p)
+    }
+
+    pub fn inverse_vector(&self, v: &Vector3) -> Vector3 {
+        self.inverse.vector(v)
+    }
+
+    //BOX AROUND THE EIGHT TRANSFORMED CORNERS
+    pub fn bounds(&self, bounds: &Aabb) -> Aabb {
+        let corner = |index: usize| -> Point3 {
+            let pick = |axis: usize| if index >> axis & 1 == 0 {bounds.min[axis]} else {bounds.max[axis]};
+            self.point(&V3!(pick(0), pick(1), pick(2)))
+        };
+        (1..8).fold(Aabb::new(corner(0), corner(0)), |result: Aabb, index: usize| Aabb::union(&result, &Aabb::new(corner(index), corner(index))))
+    }
+}
+
+//ONE FACTOR OF A TRANSFORM, KEPT SEPARATE SO ITS PARAMETERS CAN BE INTERPOLATED
+#[derive(Copy, Clone)]
+pub enum Step {
+    Translate(Vector3),
+    Rotate(Vector3, f32),
+    Scale(Vector3),
+    Matrix(Transform),
+}
+
+impl Step {
+    //NONE FOR SCALES BY ZERO
+    pub fn transform(&self) -> Option<Transform> {
+        match self {
+            Step::Translate(offset) => Some(Transform::translation(offset)),
+            Step::Rotate(axis, degrees) => Some(Transform::rotation(axis, *degrees)),
+            Step::Scale(factors) => Transform::scaling(factors),
+            Step::Matrix(transform) => Some(*transform),
+        }
+    }
+
+    pub fn compose(steps: &[Step]) -> Option<Transform> {
+        steps.iter().try_fold(Transform::IDENTITY, |transform: Transform, step: &Step| Some(transform.then(&step.transform()?)))
+    }
+}
+
+//STEPS GO LINEARLY FROM THEIR FIRST TO THEIR SECOND KEYFRAME BETWEEN TIME 0 AND 1, HOLDING THEM OUTSIDE
+pub struct Motion {
+    steps: Vec<[Step; 2]>,
+}
+
+impl Motion {
+    //KEYFRAMES MUST PAIR STEPS OF THE SAME KIND, ROTATIONS KEEP THEIR AXIS, MATRICES AND THE SIGNS OF SCALES CANNOT CHANGE
+    pub fn new(start: &[Step], end: &[Step]) -> Option<Motion> {
+        if start.len() != end.len() {
+            return None;
+        }
+        for pair in start.iter().zip(end.iter()) {
+            let valid: bool = match pair {
+                (Step::Translate(_), Step::Translate(_)) => true,
+                (Step::Rotate(a, _), Step::Rotate(b, _)) => (a.unit_vector() - b.unit_vector()).length_squared() < 1e-6,
+                (Step::Scale(a), Step::Scale(b)) => (0..3).all(|axis: usize| a[axis] * b[axis] > 0.0),
+                (Step::Matrix(a), Step::Matrix(b)) => a.matrix.m == b.matrix.m,
+                _ => false,
+            };
+            if !valid {
+                return None;
+            }
+        }
+        Some(Motion { steps: start.iter().zip(end.iter()).map(|(a, b)| [*a, *b]).collect() })
+    }
+
+    //UPPER BOUND ON HOW FAST ANY POINT OF bounds MOVES PER UNIT OF TIME, EACH STEP ADDS ITS OWN RATE
+    //TO THE SPEED OF THE STEPS BEFORE IT, STRETCHED BY ITS LARGEST GAIN
+    pub fn speed(&self, bounds: &Aabb) -> f32 {
+        //FURTHEST THE POINTS GOING INTO EACH STEP GET FROM THE ORIGIN, AT ANY TIME
+        let mut radius: f32 = (0..8).map(|index: usize| {
+            let pick = |axis: usize| if index >> axis & 1 == 0 {bounds.min[axis]} else {bounds.max[axis]};
+            V3!(pick(0), pick(1), pick(2)).length()
+        }).fold(0.0, f32::max);
+        let mut speed: f32 = 0.0;
+        for [start, end] in &self.steps {
+            let (rate, gain, offset): (f32, f32, f32) = match (start, end) {
+                (Step::Translate(a), Step::Translate(b)) => ((*b - *a).length(), 1.0, f32::max(a.length(), b.length())),
+                (Step::Rotate(_, a), Step::Rotate(_, b)) => ((b - a).to_radians().abs() * radius, 1.0, 0.0),
+                (Step::Scale(a), Step::Scale(b)) => {
+                    let gain: f32 = (0..3).map(|axis: usize| f32::max(a[axis].abs(), b[axis].abs())).fold(0.0, f32::max);
+                    ((*b - *a).length() * radius, gain, 0.0)
+                }
+                (Step::Matrix(transform), _) => {
+                    let m: &[[f32; 4]; 4] = &transform.matrix.m;
+                    let gain: f32 = (0..3).flat_map(|i: usize| (0..3).map(move |j: usize| m[i][j] * m[i][j])).sum::<f32>().sqrt();
+                    (0.0, gain, V3!(m[0][3], m[1][3], m[2][3]).length())
+                }
+                _ => (0.0, 1.0, 0.0),
+            };
+            speed = gain * speed + rate;
+            radius = gain * radius + offset;
+        }
+        speed
+    }
+
+    pub fn at(&self, time: f32) -> Transform {
+        let time: f32 = time.clamp(0.0, 1.0);
+        let lerp = |a: &Vector3, b: &Vector3| *a + time * (*b - *a);
+        self.steps.iter().fold(Transform::IDENTITY, |transform: Transform, [start, end]| {
+            let step: Step = match (start, end) {
+                (Step::Translate(a), Step::Translate(b)) => Step::Translate(lerp(a, b)),
+                (Step::Rotate(axis, a), Step::Rotate(_, b)) => Step::Rotate(*axis, a + time * (b - a)),
+                (Step::Scale(a), Step::Scale(b)) => Step::Scale(lerp(a, b)),
+                _ => *start,
+            };
+            transform.then(&step.transform().unwrap_or(Transform::IDENTITY))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &Vector3, b: &Vector3) {
+        assert!((*a - *b).length() < 1e-5, "{:?} is not {:?}", a, b);
+    }
+
+    #[test]
+    fn normals_stay_perpendicular_under_non_uniform_scale() {
+        let transform: Transform = Transform::scaling(&V3!(4.0, 1.0, 0.5)).unwrap().then(&Transform::rotation(&V3!(0.0, 0.0, 1.0), 30.0));
+        //THE PLANE x + y + z = 0, SPANNED BY TWO TANGENTS
+        let normal: Vector3 = V3!(1.0, 1.0, 1.0);
+        let tangents: [Vector3; 2] = [V3!(1.0, -1.0, 0.0), V3!(0.0, 1.0, -1.0)];
+        let transformed: Vector3 = transform.normal(&normal);
+        for tangent in tangents.iter() {
+            assert!(Vector3::dot(&transformed, &transform.vector(tangent)).abs() < 1e-5);
+        }
+        //TRANSFORMING THE NORMAL AS A VECTOR WOULD TILT IT OFF THE SURFACE
+        assert!(Vector3::dot(&transform.vector(&normal), &transform.vector(&tangents[0])).abs() > 1.0);
+    }
+
+    #[test]
+    fn inverse_point_undoes_point() {
+        let transform: Transform = Step::compose(&[Step::Scale(V3!(2.0, 3.0, 4.0)), Step::Rotate(V3!(1.0, 1.0, 0.0), 70.0), Step::Translate(V3!(1.0, 2.0, 3.0))]).unwrap();
+        let point: Point3 = V3!(0.3, -1.2, 5.0);
+        assert_close(&transform.inverse_point(&transform.point(&point)), &point);
+    }
+
+    #[test]
+    fn bounds_of_a_rotated_box_enclose_its_corners() {
+        let bounds: Aabb = Aabb::new(V3!(-1.0, -1.0, -1.0), V3!(1.0, 1.0, 1.0));
+        let rotated: Aabb = Transform::rotation(&V3!(0.0, 1.0, 0.0), 45.0).then(&Transform::translation(&V3!(10.0, 0.0, 0.0))).bounds(&bounds);
+        let half: f32 = f32::sqrt(2.0);
+        assert_close(&rotated.min, &V3!(10.0 - half, -1.0, -half));
+        assert_close(&rotated.max, &V3!(10.0 + half, 1.0, half));
+    }
+}
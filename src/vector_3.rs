@@ -136,10 +136,20 @@ impl Vector3 {
         V3!(rho * f32::sin(phi) * f32::cos(theta), rho * f32::sin(phi) * f32::sin(theta), rho * f32::cos(phi))
     }
 
+    //UNIFORM OVER THE SPHERE, SO normal + random_unit_vector IS COSINE DISTRIBUTED
     pub fn random_unit_vector(rng: &mut xorshift::StdRng) -> Vector3 {
         let theta: f32 = rng.next_f32() * 2.0 * PI;
-        let phi: f32   = rng.next_f32() * PI;
-        V3!(f32::sin(phi) * f32::cos(theta), f32::sin(phi) * f32::sin(theta), f32::cos(phi))
+        let z: f32     = 1.0 - 2.0 * rng.next_f32();
+        let r: f32     = f32::sqrt(f32::max(0.0, 1.0 - z * z));
+        V3!(r * f32::cos(theta), r * f32::sin(theta), z)
+    }
+
+    //TWO UNIT VECTORS COMPLETING AN ORTHONORMAL BASIS WITH THE UNIT VECTOR n
+    pub fn orthonormal_basis(n: &Vector3) -> (Vector3, Vector3) {
+        let sign: f32 = f32::copysign(1.0, n.z);
+        let a: f32 = -1.0 / (sign + n.z);
+        let b: f32 = n.x * n.y * a;
+        (V3!(1.0 + sign * n.x * n.x * a, sign * b, -sign * n.x), V3!(b, sign + n.y * n.y * a, -n.y))
     }
 
     pub fn reflect(v: &Vector3, n: &Vector3) -> Vector3 {
@@ -150,6 +160,28 @@ impl Vector3 {
         let cos_theta: f32 = f32::min(Vector3::dot(&-*uv, n), 1.0);
         let r_out_perp: Vector3 =  etai_over_etat * (*uv + cos_theta * *n);
         let r_out_parallel: Vector3 = -f32::sqrt((1.0 - r_out_perp.length_squared()).abs()) * *n;
-        return r_out_perp + r_out_parallel;
+        r_out_perp + r_out_parallel
     }
-}
\ No newline at end of file
+}
+impl ops::Index<usize> for Vector3 {
+    type Output = f32;
+
+    fn index(&self, axis: usize) -> &f32 {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("axis {} out of range for Vector3", axis),
+        }
+    }
+}
+
+impl Vector3 {
+    pub fn min(v: &Vector3, w: &Vector3) -> Vector3 {
+        V3!(f32::min(v.x, w.x), f32::min(v.y, w.y), f32::min(v.z, w.z))
+    }
+
+    pub fn max(v: &Vector3, w: &Vector3) -> Vector3 {
+        V3!(f32::max(v.x, w.x), f32::max(v.y, w.y), f32::max(v.z, w.z))
+    }
+}